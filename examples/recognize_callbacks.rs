@@ -24,11 +24,19 @@ async fn main() -> Result<(), Box<dyn Error>> {
     // Create a callbacks for the recognizer.
     // The callbacks are used to get information about the recognition process.
     let callbacks = recognizer::Callback::default()
-        .on_start_detected(|id, offset| async move {
-            tracing::info!("Start detected: {:?} - {:?}", id, offset);
+        .on_start_detected(|event| async move {
+            tracing::info!(
+                "Start detected: {:?} - {:?}",
+                event.request_id(),
+                event.offset()
+            );
         })
-        .on_recognized(|id, result, _offset, _duration, _raw| async move {
-            tracing::info!("Recognized: {:?} - {:?}", id, result);
+        .on_recognized(|event| async move {
+            tracing::info!(
+                "Recognized: {:?} - {:?}",
+                event.request_id(),
+                event.result()
+            );
         })
         .on_session_end(|id| async move {
             tracing::info!("Session end: {:?}", id);
@@ -42,8 +42,8 @@ async fn main() {
         // Currently is not possible to configure better the silence times and other parameters.
         // but will be implemented in the future.
 
-        if let Ok(recognizer::Event::Recognized(_, result, ..)) = event {
-            tracing::info!("Recognized: {:?}", result.text);
+        if let Ok(recognizer::Event::Recognized(recognized)) = event {
+            tracing::info!("Recognized: {:?}", recognized.result().text);
         }
     }
 }
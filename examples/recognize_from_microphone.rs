@@ -1,12 +1,11 @@
 use azure_speech::recognizer;
-use azure_speech::recognizer::AudioDevice;
+use azure_speech::recognizer::{AudioDevice, BufferPolicy, PushAudioInputStream};
 use azure_speech::Auth;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::SampleFormat as CPALSampleFormat;
 use std::env;
 use std::error::Error;
-use tokio_stream::wrappers::ReceiverStream;
-use tokio_stream::{Stream, StreamExt};
+use tokio_stream::StreamExt;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -22,7 +21,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
         env::var("AZURE_SUBSCRIPTION_KEY").expect("Subscription set on AZURE_SUBSCRIPTION_KEY env"),
     );
 
-    let config = recognizer::Config::default();
+    // The microphone audio is usually 44.1 or 48 kHz stereo: convert it to the format preferred by the service.
+    let config = recognizer::Config::default().enable_audio_conversion();
 
     let client = recognizer::Client::connect(auth, config)
         .await
@@ -31,7 +31,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
     // Using this utility, I'm creating an audio stream from the default input device.
     // The audio headers are sent first, then the audio data.
     // As the audio is raw, the WAV format is used.
-    let (stream, microphone) = listen_from_default_input().await;
+    let (header, audio, microphone) = listen_from_default_input().await;
+    // The header is not written in the push stream: with `DropOldest`, it could be dropped.
+    let stream = tokio_stream::iter(vec![header]).chain(audio);
 
     // Start the microphone.
     microphone.play().expect("play failed");
@@ -50,8 +52,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
     while let Some(event) = events.next().await {
         tracing::info!("{:?}", event);
 
-        // if let Ok(recognizer::Event::Recognized(_, result, _, _, _)) = event {
-        //     tracing::info!("recognized: {:?}", result.text);
+        // if let Ok(recognizer::Event::Recognized(recognized)) = event {
+        //     tracing::info!("recognized: {:?}", recognized.result().text);
         // }
     }
 
@@ -62,7 +64,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
 // This utility function creates a stream from the default input device.
 // The audio headers are sent first, then the audio data.
-async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stream) {
+async fn listen_from_default_input() -> (Vec<u8>, PushAudioInputStream, cpal::Stream) {
     let host = cpal::default_host();
     let device = host
         .default_input_device()
@@ -74,22 +76,20 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
     tracing::info!("Using input device: {:?}", device.name());
     tracing::info!("Default input config: {:?}", device_config);
 
-    let (tx, rx) = tokio::sync::mpsc::channel(1024);
-
-    tx.send(
-        hound::WavSpec {
-            sample_rate: device_config.sample_rate().0,
-            channels: device_config.channels(),
-            bits_per_sample: (device_config.sample_format().sample_size() * 8) as u16,
-            sample_format: match device_config.sample_format().is_float() {
-                true => hound::SampleFormat::Float,
-                false => hound::SampleFormat::Int,
-            },
-        }
-        .into_header_for_infinite_file(),
-    )
-    .await
-    .expect("Failed to send wav header");
+    // When the recognizer is not fast enough, the oldest audio is dropped.
+    // The stream ends when the writer, moved into the input callback, is dropped with the microphone.
+    let (writer, push_stream) = PushAudioInputStream::new(1024 * 1024, BufferPolicy::DropOldest);
+
+    let header = hound::WavSpec {
+        sample_rate: device_config.sample_rate().0,
+        channels: device_config.channels(),
+        bits_per_sample: (device_config.sample_format().sample_size() * 8) as u16,
+        sample_format: match device_config.sample_format().is_float() {
+            true => hound::SampleFormat::Float,
+            false => hound::SampleFormat::Int,
+        },
+    }
+    .into_header_for_infinite_file();
 
     let config = device_config.clone().into();
 
@@ -99,8 +99,8 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         CPALSampleFormat::I8 => device.build_input_stream(
             &config,
             move |data: &[i8], _| {
-                data.iter()
-                    .for_each(|d| tx.try_send(d.to_le_bytes().to_vec()).unwrap_or(()))
+                let bytes: Vec<u8> = data.iter().flat_map(|d| d.to_le_bytes()).collect();
+                writer.write(&bytes).unwrap_or(())
             },
             err,
             None,
@@ -108,8 +108,8 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         CPALSampleFormat::U8 => device.build_input_stream(
             &config,
             move |data: &[u8], _| {
-                data.iter()
-                    .for_each(|d| tx.try_send(d.to_le_bytes().to_vec()).unwrap_or(()))
+                let bytes: Vec<u8> = data.iter().flat_map(|d| d.to_le_bytes()).collect();
+                writer.write(&bytes).unwrap_or(())
             },
             err,
             None,
@@ -117,8 +117,8 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         CPALSampleFormat::I16 => device.build_input_stream(
             &config,
             move |data: &[i16], _| {
-                data.iter()
-                    .for_each(|d| tx.try_send(d.to_le_bytes().to_vec()).unwrap_or(()))
+                let bytes: Vec<u8> = data.iter().flat_map(|d| d.to_le_bytes()).collect();
+                writer.write(&bytes).unwrap_or(())
             },
             err,
             None,
@@ -126,8 +126,8 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         CPALSampleFormat::U16 => device.build_input_stream(
             &config,
             move |data: &[u16], _| {
-                data.iter()
-                    .for_each(|d| tx.try_send(d.to_le_bytes().to_vec()).unwrap_or(()))
+                let bytes: Vec<u8> = data.iter().flat_map(|d| d.to_le_bytes()).collect();
+                writer.write(&bytes).unwrap_or(())
             },
             err,
             None,
@@ -135,8 +135,8 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         CPALSampleFormat::I32 => device.build_input_stream(
             &config,
             move |data: &[i32], _| {
-                data.iter()
-                    .for_each(|d| tx.try_send(d.to_le_bytes().to_vec()).unwrap_or(()))
+                let bytes: Vec<u8> = data.iter().flat_map(|d| d.to_le_bytes()).collect();
+                writer.write(&bytes).unwrap_or(())
             },
             err,
             None,
@@ -144,8 +144,8 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         CPALSampleFormat::U32 => device.build_input_stream(
             &config,
             move |data: &[u32], _| {
-                data.iter()
-                    .for_each(|d| tx.try_send(d.to_le_bytes().to_vec()).unwrap_or(()))
+                let bytes: Vec<u8> = data.iter().flat_map(|d| d.to_le_bytes()).collect();
+                writer.write(&bytes).unwrap_or(())
             },
             err,
             None,
@@ -153,8 +153,8 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         CPALSampleFormat::F32 => device.build_input_stream(
             &config,
             move |data: &[f32], _| {
-                data.iter()
-                    .for_each(|d| tx.try_send(d.to_le_bytes().to_vec()).unwrap_or(()))
+                let bytes: Vec<u8> = data.iter().flat_map(|d| d.to_le_bytes()).collect();
+                writer.write(&bytes).unwrap_or(())
             },
             err,
             None,
@@ -162,8 +162,8 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         CPALSampleFormat::I64 => device.build_input_stream(
             &config,
             move |data: &[i64], _| {
-                data.iter()
-                    .for_each(|d| tx.try_send(d.to_le_bytes().to_vec()).unwrap_or(()))
+                let bytes: Vec<u8> = data.iter().flat_map(|d| d.to_le_bytes()).collect();
+                writer.write(&bytes).unwrap_or(())
             },
             err,
             None,
@@ -171,8 +171,8 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         CPALSampleFormat::U64 => device.build_input_stream(
             &config,
             move |data: &[u64], _| {
-                data.iter()
-                    .for_each(|d| tx.try_send(d.to_le_bytes().to_vec()).unwrap_or(()))
+                let bytes: Vec<u8> = data.iter().flat_map(|d| d.to_le_bytes()).collect();
+                writer.write(&bytes).unwrap_or(())
             },
             err,
             None,
@@ -180,8 +180,8 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         CPALSampleFormat::F64 => device.build_input_stream(
             &config,
             move |data: &[f64], _| {
-                data.iter()
-                    .for_each(|d| tx.try_send(d.to_le_bytes().to_vec()).unwrap_or(()))
+                let bytes: Vec<u8> = data.iter().flat_map(|d| d.to_le_bytes()).collect();
+                writer.write(&bytes).unwrap_or(())
             },
             err,
             None,
@@ -189,5 +189,5 @@ async fn listen_from_default_input() -> (impl Stream<Item = Vec<u8>>, cpal::Stre
         _ => panic!("Unsupported sample format"),
     }
     .expect("Failed to build input stream");
-    (ReceiverStream::new(rx), stream)
+    (header, push_stream, stream)
 }
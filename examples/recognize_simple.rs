@@ -57,21 +57,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
         // Each event is a part of the recognition process.
         match event {
             // as example the Recognized event will give you the result of the recognition.
-            Ok(recognizer::Event::Recognized(
-                request_id,
-                result,
-                offset,
-                duration,
-                raw_message,
-            )) => {
-                tracing::info!("Recognized session: {:?}", request_id);
-                tracing::info!("Result: {:?}", result);
-                tracing::info!("Offset: {:?}", offset);
-                tracing::info!("Duration: {:?}", duration);
+            Ok(recognizer::Event::Recognized(recognized)) => {
+                tracing::info!("Recognized session: {:?}", recognized.request_id());
+                tracing::info!("Result: {:?}", recognized.result());
+                tracing::info!("Offset: {:?}", recognized.offset());
+                tracing::info!("Duration: {:?}", recognized.duration());
 
                 // the raw message is the json message received from the service.
                 // You can use it to extract more information when needed.
-                tracing::info!("Raw message: {:?}", raw_message);
+                tracing::info!("Raw message: {:?}", recognized.raw());
             }
             _ => {
                 tracing::info!("Event: {:?}", event);
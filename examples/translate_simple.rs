@@ -0,0 +1,59 @@
+use azure_speech::stream::StreamExt;
+use azure_speech::{recognizer, synthesizer, translator, Auth};
+use std::env;
+use std::error::Error;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    if env::var_os("RUST_LOG").is_none() {
+        env::set_var("RUST_LOG", "INFO");
+    }
+    tracing_subscriber::fmt::init();
+
+    // Add your Azure region and subscription key to the environment variables.
+    let auth = Auth::from_subscription(
+        env::var("AZURE_REGION").expect("Region set on AZURE_REGION env"),
+        env::var("AZURE_SUBSCRIPTION_KEY").expect("Subscription set on AZURE_SUBSCRIPTION_KEY env"),
+    );
+
+    // Set the configuration for the translator.
+    //
+    // The source speech is english, and it will be translated into german and italian.
+    // The german translation will also be synthesized with the given voice.
+    let config = translator::Config::default()
+        .set_language(recognizer::Language::EnUs)
+        .add_target_language("de")
+        .add_target_language("it")
+        .set_voice(synthesizer::Voice::DeDeKatjaNeural);
+
+    let client = translator::Client::connect(auth, config)
+        .await
+        .expect("to connect to azure");
+
+    let mut stream = client
+        .translate_file("tests/audios/examples_sample_files_turn_on_the_lamp.wav")
+        .await
+        .expect("to translate");
+
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(translator::Event::Translated(translated)) => {
+                let result = translated.into_result();
+                tracing::info!("Recognized: {:?}", result.text);
+                for (language, text) in result.translations {
+                    tracing::info!("Translated ({}): {:?}", language, text);
+                }
+            }
+            Ok(translator::Event::Synthesising(_, audio)) => {
+                tracing::info!("Synthesized audio chunk: {} bytes", audio.len());
+            }
+            _ => {
+                tracing::info!("Event: {:?}", event);
+            }
+        }
+    }
+
+    tracing::info!("Completed!");
+
+    Ok(())
+}
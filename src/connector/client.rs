@@ -1,4 +1,7 @@
 use futures_util::SinkExt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio_stream::wrappers::BroadcastStream;
@@ -47,6 +50,8 @@ enum InternalMessage {
             crate::Result<broadcast::Receiver<crate::Result<tokio_websockets::Message>>>,
         >,
     ),
+    Reconnect(oneshot::Sender<crate::Result<()>>),
+    Connect(oneshot::Sender<crate::Result<Client>>),
     Disconnect,
 }
 
@@ -60,6 +65,12 @@ impl Client {
     fn new(channel: mpsc::Sender<InternalMessage>) -> Self {
         Self { channel }
     }
+
+    /// Create a client without any connection behind it.
+    #[cfg(test)]
+    pub(crate) fn disconnected() -> Self {
+        Self::new(mpsc::channel(1).0)
+    }
 }
 
 impl Client {
@@ -90,6 +101,28 @@ impl Client {
         Ok(())
     }
 
+    /// Connect again to the server, if the connection was lost.
+    pub(crate) async fn reconnect(&self) -> crate::Result<()> {
+        let (sender, receiver) = oneshot::channel();
+        self.channel
+            .send(InternalMessage::Reconnect(sender))
+            .await?;
+        receiver.await.map_err(|_| {
+            crate::Error::InternalError("Failed to reconnect".to_string())
+        })?
+    }
+
+    /// Open another connection to the same server.
+    pub(crate) async fn new_connection(&self) -> crate::Result<Self> {
+        let (sender, receiver) = oneshot::channel();
+        self.channel
+            .send(InternalMessage::Connect(sender))
+            .await?;
+        receiver.await.map_err(|_| {
+            crate::Error::InternalError("Failed to open a new connection".to_string())
+        })?
+    }
+
     /// Stream messages from the server.
     pub async fn stream(&self) -> crate::Result<impl Stream<Item = crate::Result<crate::Message>>> {
         let (sender, receiver) = oneshot::channel();
@@ -127,6 +160,17 @@ impl Client {
 
 impl Client {
     pub async fn connect(client: ClientBuilder<'static>) -> crate::Result<Self> {
+        Self::open(Arc::new(client)).await
+    }
+
+    /// Connect with the shared builder. Boxed, as the connection task opens the new connections.
+    fn open_shared(
+        client: Arc<ClientBuilder<'static>>,
+    ) -> Pin<Box<dyn Future<Output = crate::Result<Self>> + Send>> {
+        Box::pin(Self::open(client))
+    }
+
+    async fn open(client: Arc<ClientBuilder<'static>>) -> crate::Result<Self> {
         let (mut stream, _res) = client.connect().await?;
         let (sender, mut receiver) = mpsc::channel(16);
         tokio::spawn(async move {
@@ -146,7 +190,7 @@ impl Client {
                             },
                             InternalMessage::Subscribe(c) => {
                                 if !connected {
-                                    match reconnect_with_attempts(&client, 3).await {
+                                    match reconnect_with_attempts(&*client, 3).await {
                                         Ok(new_stream) => {
                                             connected = true;
                                             stream = new_stream;
@@ -160,6 +204,28 @@ impl Client {
 
                                 let _ = c.send(Ok(broadcaster.subscribe()));
                             },
+                            InternalMessage::Reconnect(c) => {
+                                if !connected {
+                                    match reconnect_with_attempts(&*client, 3).await {
+                                        Ok(new_stream) => {
+                                            connected = true;
+                                            stream = new_stream;
+                                        }
+                                        Err(err) => {
+                                            let _ = c.send(Err(err));
+                                            continue;
+                                        }
+                                    }
+                                }
+
+                                let _ = c.send(Ok(()));
+                            },
+                            InternalMessage::Connect(c) => {
+                                let client = client.clone();
+                                tokio::spawn(async move {
+                                    let _ = c.send(Client::open_shared(client).await);
+                                });
+                            },
                             InternalMessage::Disconnect => {
                                 let _ = stream.close().await;
                                 break;
@@ -171,6 +237,7 @@ impl Client {
                             // Receiving `None` here means the socket has been disconnected and can no longer receive messages.
                             // We set `connected` to false just to make sure that the stream isn't polled again until we're reconnected.
                             connected = false;
+                            let _ = broadcaster.send(Err(crate::Error::ServerDisconnect("connection closed".to_string())));
                             continue;
                         };
                         match msg {
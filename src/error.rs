@@ -7,9 +7,11 @@ use std::sync::PoisonError;
 pub type Result<T> = result::Result<T, Error>;
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// Error enum, used to represent errors in the library.
 pub enum Error {
     IOError(String),
+    ConfigError(String),
     InvalidResponse(String),
     ParseError(String),
     InternalError(String),
@@ -74,6 +76,7 @@ impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::IOError(s) => write!(f, "IO error: {s}"),
+            Self::ConfigError(s) => write!(f, "Invalid configuration: {s}"),
             Self::InvalidResponse(s) => write!(f, "Invalid response from server: {s}"),
             Self::ParseError(s) => write!(f, "Failed to parse response from server: {s}"),
             Self::InternalError(s) => write!(f, "Internal error: {s}"),
@@ -10,6 +10,7 @@
 //! ## Core Functionalities
 //! - [X] Speech to Text [recognizer]
 //! - [X] Text to Speech [synthesizer]
+//! - [X] Speech Translation [translator]
 //!
 //! For comprehensive information on Microsoft Speech Service, refer to the official
 //! documentation [here](https://learn.microsoft.com/en-us/azure/ai-services/speech-service/speech-sdk).
@@ -29,11 +30,13 @@ pub mod connector;
 mod error;
 mod event;
 mod stream_ext;
+mod ticks;
 mod utils;
 
 mod callback;
 pub mod recognizer;
 pub mod synthesizer;
+pub mod translator;
 
 pub use auth::*;
 pub use connector::*;
@@ -41,6 +44,7 @@ pub use error::*;
 
 pub use event::*;
 pub use stream_ext::*;
+pub use ticks::*;
 
 pub mod stream {
     //! Re-export of `tokio_stream` crate.
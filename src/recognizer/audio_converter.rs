@@ -0,0 +1,346 @@
+use crate::recognizer::{SampleFormat, WavSpec};
+use std::f64::consts::PI;
+
+/// The audio spec preferred by the service.
+pub(crate) const TARGET_SPEC: WavSpec = WavSpec {
+    sample_rate: 16000,
+    bits_per_sample: 16,
+    channels: 1,
+    sample_format: SampleFormat::Int,
+};
+
+/// The number of zero crossings of the sinc kernel, on each side.
+const ZERO_CROSSINGS: f64 = 16.0;
+
+/// Converts PCM audio to 16 kHz mono 16-bit.
+///
+/// The channels are down-mixed by averaging them, and the sample rate is converted
+/// with a windowed sinc interpolation, low-passed at the lowest of the two Nyquist frequencies.
+#[derive(Debug)]
+pub(crate) struct AudioConverter {
+    spec: WavSpec,
+    /// Bytes of an incomplete frame, waiting for the next chunk.
+    pending: Vec<u8>,
+    resampler: Option<Resampler>,
+}
+
+impl AudioConverter {
+    /// Create the converter for the given spec.
+    ///
+    /// Returns `None` if the spec is already the target one, or it cannot be converted.
+    pub(crate) fn new(spec: WavSpec) -> Option<Self> {
+        if spec == TARGET_SPEC
+            || !matches!(spec.sample_format, SampleFormat::Int | SampleFormat::Float)
+        {
+            return None;
+        }
+
+        Some(Self {
+            spec,
+            pending: vec![],
+            resampler: (spec.sample_rate != TARGET_SPEC.sample_rate)
+                .then(|| Resampler::new(spec.sample_rate, TARGET_SPEC.sample_rate)),
+        })
+    }
+
+    /// Convert the next chunk of audio.
+    pub(crate) fn process(&mut self, data: &[u8]) -> Vec<u8> {
+        self.pending.extend_from_slice(data);
+        let frame_size = self.spec.block_align() as usize;
+        let complete = self.pending.len() - self.pending.len() % frame_size;
+
+        let mono: Vec<f64> = self.pending[..complete]
+            .chunks_exact(frame_size)
+            .map(|frame| down_mix(frame, &self.spec))
+            .collect();
+        self.pending.drain(..complete);
+
+        let samples = match self.resampler.as_mut() {
+            Some(resampler) => resampler.process(&mono),
+            None => mono,
+        };
+        encode(&samples)
+    }
+
+    /// Convert the audio still buffered, at the end of the stream.
+    pub(crate) fn flush(&mut self) -> Vec<u8> {
+        self.pending.clear();
+        match self.resampler.as_mut() {
+            Some(resampler) => encode(&resampler.flush()),
+            None => vec![],
+        }
+    }
+}
+
+/// Decode a frame of interleaved samples to a mono sample, averaging the channels.
+pub(crate) fn down_mix(frame: &[u8], spec: &WavSpec) -> f64 {
+    let sample_size = frame.len() / spec.channels as usize;
+    let sum: f64 = frame
+        .chunks_exact(sample_size)
+        .map(|sample| decode(sample, spec.sample_format))
+        .sum();
+    sum / spec.channels as f64
+}
+
+/// Decode a little-endian sample to the `[-1.0, 1.0]` range.
+fn decode(sample: &[u8], sample_format: SampleFormat) -> f64 {
+    match (sample_format, sample.len()) {
+        (SampleFormat::Int, 1) => (sample[0] as f64 - 128.0) / 128.0,
+        (SampleFormat::Int, 2) => i16::from_le_bytes([sample[0], sample[1]]) as f64 / 32768.0,
+        (SampleFormat::Int, 3) => {
+            // Shift the 24 bits in the upper bytes of an i32, to keep the sign.
+            i32::from_le_bytes([0, sample[0], sample[1], sample[2]]) as f64 / 2147483648.0
+        }
+        (SampleFormat::Int, 4) => {
+            i32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]) as f64 / 2147483648.0
+        }
+        (SampleFormat::Float, 4) => {
+            f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]) as f64
+        }
+        (SampleFormat::Float, 8) => f64::from_le_bytes(sample.try_into().unwrap()),
+        _ => 0.0,
+    }
+}
+
+/// Encode the samples to 16-bit little-endian.
+fn encode(samples: &[f64]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|s| ((s * 32768.0).round().clamp(-32768.0, 32767.0) as i16).to_le_bytes())
+        .collect()
+}
+
+/// A streaming windowed sinc resampler.
+#[derive(Debug)]
+struct Resampler {
+    /// The input samples per output sample.
+    step: f64,
+    /// The cutoff frequency, relative to the input Nyquist frequency.
+    cutoff: f64,
+    /// The half width of the kernel, in input samples.
+    half_width: usize,
+    /// The input samples not consumed yet, including the history needed by the kernel.
+    input: Vec<f64>,
+    /// The position of the next output sample in `input`.
+    position: f64,
+    /// The sample rates, and the number of samples received and produced, to flush exactly.
+    rates: (u64, u64),
+    received: u64,
+    produced: u64,
+}
+
+impl Resampler {
+    fn new(from: u32, to: u32) -> Self {
+        let step = from as f64 / to as f64;
+        let cutoff = (1.0 / step).min(1.0);
+        let half_width = (ZERO_CROSSINGS / cutoff).ceil() as usize;
+
+        Self {
+            step,
+            cutoff,
+            half_width,
+            // Start with silence, so the first output sample is aligned with the first input one.
+            input: vec![0.0; half_width],
+            position: half_width as f64,
+            rates: (from as u64, to as u64),
+            received: 0,
+            produced: 0,
+        }
+    }
+
+    fn process(&mut self, samples: &[f64]) -> Vec<f64> {
+        self.input.extend_from_slice(samples);
+        self.received += samples.len() as u64;
+
+        let mut output = vec![];
+        while (self.position.floor() as usize) + self.half_width < self.input.len() {
+            output.push(self.interpolate(self.position));
+            self.position += self.step;
+        }
+
+        // Keep only the history needed by the next output samples.
+        let consumed = (self.position.floor() as usize).saturating_sub(self.half_width);
+        self.input.drain(..consumed.min(self.input.len()));
+        self.position -= consumed as f64;
+
+        self.produced += output.len() as u64;
+        output
+    }
+
+    fn flush(&mut self) -> Vec<f64> {
+        let (from, to) = self.rates;
+        let expected = (self.received * to + from - 1) / from;
+        let count = expected.saturating_sub(self.produced) as usize;
+
+        // The padding is not part of the audio.
+        let mut output = self.process(&vec![0.0; self.half_width]);
+        self.received -= self.half_width as u64;
+        output.truncate(count);
+        output
+    }
+
+    fn interpolate(&self, position: f64) -> f64 {
+        let center = position.floor() as usize;
+        let first = center + 1 - self.half_width;
+        let last = center + self.half_width;
+
+        (first..=last)
+            .map(|i| {
+                let distance = position - i as f64;
+                self.input[i] * self.kernel(distance)
+            })
+            .sum()
+    }
+
+    fn kernel(&self, distance: f64) -> f64 {
+        let x = distance / self.half_width as f64;
+        if x.abs() >= 1.0 {
+            return 0.0;
+        }
+
+        // Blackman window.
+        let window = 0.42 + 0.5 * (PI * x).cos() + 0.08 * (2.0 * PI * x).cos();
+        let t = distance * self.cutoff;
+        let sinc = if t == 0.0 {
+            1.0
+        } else {
+            (PI * t).sin() / (PI * t)
+        };
+
+        self.cutoff * sinc * window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(
+        sample_rate: u32,
+        bits_per_sample: u16,
+        channels: u16,
+        format: SampleFormat,
+    ) -> WavSpec {
+        WavSpec {
+            sample_rate,
+            bits_per_sample,
+            channels,
+            sample_format: format,
+        }
+    }
+
+    fn sine(frequency: f64, sample_rate: u32, seconds: f64, amplitude: f64) -> Vec<f64> {
+        let count = (sample_rate as f64 * seconds) as usize;
+        (0..count)
+            .map(|i| amplitude * (2.0 * PI * frequency * i as f64 / sample_rate as f64).sin())
+            .collect()
+    }
+
+    fn to_samples(bytes: &[u8]) -> Vec<f64> {
+        bytes
+            .chunks_exact(2)
+            .map(|s| i16::from_le_bytes([s[0], s[1]]) as f64 / 32768.0)
+            .collect()
+    }
+
+    fn rms(samples: &[f64]) -> f64 {
+        (samples.iter().map(|s| s * s).sum::<f64>() / samples.len() as f64).sqrt()
+    }
+
+    fn zero_crossings(samples: &[f64]) -> usize {
+        samples
+            .windows(2)
+            .filter(|w| (w[0] < 0.0) != (w[1] < 0.0))
+            .count()
+    }
+
+    /// Convert in uneven chunks, to exercise the partial frames.
+    fn convert(spec: WavSpec, bytes: &[u8]) -> Vec<f64> {
+        let mut converter = AudioConverter::new(spec).unwrap();
+        let mut output = vec![];
+        for chunk in bytes.chunks(1001) {
+            output.extend(converter.process(chunk));
+        }
+        output.extend(converter.flush());
+        to_samples(&output)
+    }
+
+    #[test]
+    fn test_target_spec_is_not_converted() {
+        assert!(AudioConverter::new(TARGET_SPEC).is_none());
+        assert!(AudioConverter::new(spec(8000, 8, 1, SampleFormat::MuLaw)).is_none());
+    }
+
+    #[test]
+    fn test_down_mix_stereo_i16() {
+        let bytes: Vec<u8> = (0..1600)
+            .flat_map(|_| [8192i16.to_le_bytes(), (-16384i16).to_le_bytes()].concat())
+            .collect();
+
+        let output = convert(spec(16000, 16, 2, SampleFormat::Int), &bytes);
+        assert_eq!(output.len(), 1600);
+        assert!(output.iter().all(|s| (s + 0.125).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_resample_48khz_f32_sine() {
+        let input = sine(440.0, 48000, 1.0, 0.5);
+        let bytes: Vec<u8> = input
+            .iter()
+            .flat_map(|s| (*s as f32).to_le_bytes())
+            .collect();
+
+        let output = convert(spec(48000, 32, 1, SampleFormat::Float), &bytes);
+
+        // One second of audio, with the same frequency and amplitude.
+        assert_eq!(output.len(), 16000);
+        assert!((zero_crossings(&output) as i64 - 880).abs() <= 2);
+        assert!((rms(&output[1000..15000]) - 0.5 / 2f64.sqrt()).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_resample_44_1khz_i24_stereo_sine() {
+        let input = sine(1000.0, 44100, 0.5, 0.25);
+        let bytes: Vec<u8> = input
+            .iter()
+            .flat_map(|s| {
+                let sample = ((s * 8388608.0) as i32).to_le_bytes();
+                [
+                    sample[0], sample[1], sample[2], sample[0], sample[1], sample[2],
+                ]
+            })
+            .collect();
+
+        let output = convert(spec(44100, 24, 2, SampleFormat::Int), &bytes);
+
+        assert_eq!(output.len(), 8000);
+        assert!((zero_crossings(&output) as i64 - 1000).abs() <= 2);
+        assert!((rms(&output[500..7500]) - 0.25 / 2f64.sqrt()).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_resample_removes_frequencies_above_nyquist() {
+        // 12 kHz is above the 8 kHz Nyquist frequency of the output: it must not alias.
+        let input = sine(12000.0, 48000, 0.5, 0.5);
+        let bytes: Vec<u8> = input
+            .iter()
+            .flat_map(|s| ((s * 32767.0) as i16).to_le_bytes())
+            .collect();
+
+        let output = convert(spec(48000, 16, 1, SampleFormat::Int), &bytes);
+
+        assert!(rms(&output[500..7500]) < 0.01);
+    }
+
+    #[test]
+    fn test_upsample_8khz_u8() {
+        let input = sine(300.0, 8000, 0.5, 0.5);
+        let bytes: Vec<u8> = input.iter().map(|s| (s * 127.0 + 128.0) as u8).collect();
+
+        let output = convert(spec(8000, 8, 1, SampleFormat::Int), &bytes);
+
+        assert_eq!(output.len(), 8000);
+        assert!((zero_crossings(&output) as i64 - 300).abs() <= 2);
+        assert!((rms(&output[500..7500]) - 0.5 / 2f64.sqrt()).abs() < 0.02);
+    }
+}
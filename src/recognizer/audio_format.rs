@@ -19,12 +19,23 @@ pub enum AudioFormat {
     OggOpus,
     /// The audio is in MPEG format.
     Mpeg,
+    /// The audio is headerless PCM, with signed little-endian samples (unsigned for 8 bits).
+    ///
+    /// The WAV header is created from the declared spec.
+    RawPcm {
+        /// The number of samples per second.
+        sample_rate: u32,
+        /// The number of bits of each sample.
+        bits_per_sample: u16,
+        /// The number of interleaved channels.
+        channels: u16,
+    },
 }
 
 impl TryFrom<&OsStr> for AudioFormat {
     type Error = crate::Error;
     fn try_from(extension: &OsStr) -> Result<Self, crate::Error> {
-        match extension.to_str().unwrap().to_lowercase().as_str() {
+        match extension.to_string_lossy().to_lowercase().as_str() {
             "wav" | "wave" => Ok(AudioFormat::Wav),
             "flac" => Ok(AudioFormat::Flac),
             "opus" => Ok(AudioFormat::Opus),
@@ -41,9 +52,36 @@ impl TryFrom<&OsStr> for AudioFormat {
 }
 
 impl AudioFormat {
+    /// The number of bytes needed by [`AudioFormat::detect`].
+    pub const DETECTION_LEN: usize = 12;
+
+    /// Detect the audio format from the first bytes of the audio.
+    ///
+    /// Detects WAV, FLAC, Ogg, WebM and MP3/MPEG (with or without ID3 tags).
+    /// Raw PCM cannot be detected.
+    pub fn detect(head: &[u8]) -> Option<Self> {
+        match head {
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => {
+                Some(AudioFormat::Wav)
+            }
+            [b'f', b'L', b'a', b'C', ..] => Some(AudioFormat::Flac),
+            [b'O', b'g', b'g', b'S', ..] => Some(AudioFormat::OggOpus),
+            [0x1A, 0x45, 0xDF, 0xA3, ..] => Some(AudioFormat::WebmOpus),
+            [b'I', b'D', b'3', ..] => Some(AudioFormat::Mp3),
+            // MPEG audio frame sync: 11 bits set, then the version and the layer.
+            [0xFF, b, ..] if b & 0xE0 == 0xE0 => match (b >> 1) & 0x03 {
+                0b01 => Some(AudioFormat::Mp3),
+                0b10 | 0b11 => Some(AudioFormat::Mpeg),
+                // Layer `00` is reserved: it is AAC in ADTS.
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     pub(crate) fn as_content_type(&self) -> &str {
         match self {
-            AudioFormat::Wav => "audio/wav",
+            AudioFormat::Wav | AudioFormat::RawPcm { .. } => "audio/wav",
             AudioFormat::Flac => "audio/flac",
             AudioFormat::Opus => "audio/ogg; codecs=opus",
             AudioFormat::Mp3 => "audio/mpeg",
@@ -53,3 +91,45 @@ impl AudioFormat {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let cases: [(&[u8], Option<AudioFormat>); 9] = [
+            (b"RIFF\x24\0\0\0WAVEfmt ", Some(AudioFormat::Wav)),
+            (b"RIFF\x24\0\0\0AVI LIST", None),
+            (b"fLaC\0\0\0\x22", Some(AudioFormat::Flac)),
+            (b"OggS\0\x02\0\0", Some(AudioFormat::OggOpus)),
+            (&[0x1A, 0x45, 0xDF, 0xA3, 0x9F], Some(AudioFormat::WebmOpus)),
+            (b"ID3\x04\0\0\0\0", Some(AudioFormat::Mp3)),
+            (&[0xFF, 0xFB, 0x90, 0x64], Some(AudioFormat::Mp3)),
+            (&[0xFF, 0xFD, 0x90, 0x64], Some(AudioFormat::Mpeg)),
+            (&[0xFF, 0xF1, 0x50, 0x80], None),
+        ];
+
+        for (head, format) in cases {
+            assert_eq!(AudioFormat::detect(head), format, "{head:?}");
+        }
+        assert_eq!(AudioFormat::detect(b"RIF"), None);
+    }
+
+    #[test]
+    fn test_from_extension() {
+        assert_eq!(
+            AudioFormat::try_from(OsStr::new("MP3")),
+            Ok(AudioFormat::Mp3)
+        );
+        assert!(AudioFormat::try_from(OsStr::new("txt")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_from_non_utf8_extension() {
+        use std::os::unix::ffi::OsStrExt;
+
+        assert!(AudioFormat::try_from(OsStr::from_bytes(b"w\xFFv")).is_err());
+    }
+}
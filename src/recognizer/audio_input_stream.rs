@@ -0,0 +1,446 @@
+use crate::recognizer::AudioFormat;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
+use tokio_stream::Stream;
+use tracing::{debug, warn};
+
+const READ_SIZE: usize = 4096;
+
+/// What to do when the buffer of a [`PushAudioInputStream`] is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BufferPolicy {
+    /// Block the writer until there is enough space in the buffer.
+    ///
+    /// The current thread is blocked: do not use it when writing from an async task.
+    #[default]
+    Block,
+    /// Drop the oldest audio in the buffer to make space for the new one.
+    DropOldest,
+    /// Return an error to the writer.
+    Error,
+}
+
+#[derive(Debug, Default)]
+struct PushState {
+    chunks: VecDeque<Vec<u8>>,
+    buffered: usize,
+    closed: bool,
+    writers: usize,
+    waker: Option<Waker>,
+}
+
+struct PushInner {
+    state: Mutex<PushState>,
+    space: Condvar,
+    capacity: usize,
+    policy: BufferPolicy,
+}
+
+impl PushInner {
+    /// Lock the state. It stays consistent if a writer panics, so the poisoning is ignored.
+    fn state(&self) -> MutexGuard<'_, PushState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn close(&self, mut state: MutexGuard<'_, PushState>) {
+        state.closed = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.space.notify_all();
+    }
+}
+
+/// An audio input stream where the audio is pushed by the application.
+///
+/// Useful when the audio comes from callbacks (microphone, RTP, WebRTC, ...).
+/// The audio is written with the [`PushAudioWriter`] created along with the stream, and the stream
+/// is passed to the recognizer. The audio ends when the writer is closed, or when the last clone
+/// of the writer is dropped.
+/// When the recognition ends and drops the stream, the writes return an error.
+///
+/// # Example
+///
+/// ```no_run
+/// use azure_speech::recognizer::{BufferPolicy, PushAudioInputStream};
+///
+/// let (writer, stream) = PushAudioInputStream::new(64 * 1024, BufferPolicy::DropOldest);
+///
+/// // in the audio callback
+/// writer.write(&[0u8; 320]).expect("to write the audio");
+///
+/// // at the end of the audio
+/// writer.close();
+/// ```
+pub struct PushAudioInputStream {
+    inner: Arc<PushInner>,
+}
+
+impl PushAudioInputStream {
+    /// Create a new push stream, buffering up to `capacity` bytes, and the writer of its audio.
+    pub fn new(capacity: usize, policy: BufferPolicy) -> (PushAudioWriter, Self) {
+        let inner = Arc::new(PushInner {
+            state: Mutex::new(PushState {
+                writers: 1,
+                ..PushState::default()
+            }),
+            space: Condvar::new(),
+            capacity,
+            policy,
+        });
+
+        (
+            PushAudioWriter {
+                inner: inner.clone(),
+            },
+            Self { inner },
+        )
+    }
+}
+
+impl Stream for PushAudioInputStream {
+    type Item = Vec<u8>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.inner.state();
+        match state.chunks.pop_front() {
+            Some(chunk) => {
+                state.buffered -= chunk.len();
+                self.inner.space.notify_all();
+                Poll::Ready(Some(chunk))
+            }
+            None if state.closed => Poll::Ready(None),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for PushAudioInputStream {
+    fn drop(&mut self) {
+        // Nobody reads the audio anymore: the blocked writers are released.
+        self.inner.close(self.inner.state());
+    }
+}
+
+/// Writes the audio into a [`PushAudioInputStream`].
+///
+/// The writer is cloneable: all the clones write into the same stream.
+/// Dropping the last clone closes the stream, as [`close`](PushAudioWriter::close) does.
+pub struct PushAudioWriter {
+    inner: Arc<PushInner>,
+}
+
+impl PushAudioWriter {
+    /// Write the audio data into the stream.
+    ///
+    /// When the buffer is full, the configured [`BufferPolicy`] is applied.
+    pub fn write(&self, data: &[u8]) -> crate::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut state = self.inner.state();
+        loop {
+            if state.closed {
+                return Err(crate::Error::IOError(
+                    "Audio input stream is closed.".to_string(),
+                ));
+            }
+
+            // An empty buffer always accepts the data, even if bigger than the capacity.
+            if state.buffered == 0 || state.buffered + data.len() <= self.inner.capacity {
+                break;
+            }
+
+            match self.inner.policy {
+                BufferPolicy::Block => {
+                    state = self
+                        .inner
+                        .space
+                        .wait(state)
+                        .unwrap_or_else(PoisonError::into_inner)
+                }
+                BufferPolicy::DropOldest => {
+                    if let Some(chunk) = state.chunks.pop_front() {
+                        state.buffered -= chunk.len();
+                    }
+                }
+                BufferPolicy::Error => {
+                    return Err(crate::Error::IOError(
+                        "Audio input stream buffer is full.".to_string(),
+                    ))
+                }
+            }
+        }
+
+        state.buffered += data.len();
+        state.chunks.push_back(data.to_vec());
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Close the stream, for all the clones of the writer.
+    ///
+    /// The audio already written is still sent, then the end of audio is signaled to the service.
+    pub fn close(&self) {
+        self.inner.close(self.inner.state());
+    }
+}
+
+impl Clone for PushAudioWriter {
+    fn clone(&self) -> Self {
+        self.inner.state().writers += 1;
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Drop for PushAudioWriter {
+    fn drop(&mut self) {
+        let mut state = self.inner.state();
+        state.writers -= 1;
+        if state.writers == 0 {
+            self.inner.close(state);
+        }
+    }
+}
+
+/// An audio input stream where the audio is pulled from an [`AsyncRead`].
+///
+/// Useful to recognize files, network streams or pipes.
+pub struct PullAudioInputStream<R> {
+    reader: R,
+    /// Bytes already read from the reader, to yield first.
+    head: Option<Vec<u8>>,
+    done: bool,
+}
+
+impl<R> PullAudioInputStream<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Create a new pull stream from the reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            head: None,
+            done: false,
+        }
+    }
+
+    /// Create a new pull stream, detecting the audio format from the first bytes.
+    ///
+    /// The `hint` (usually from the file extension) is used when the format cannot be detected.
+    pub(crate) async fn detect_format(
+        mut reader: R,
+        hint: Option<AudioFormat>,
+    ) -> crate::Result<(Self, AudioFormat)> {
+        let mut head = vec![0; AudioFormat::DETECTION_LEN];
+        let mut filled = 0;
+        while filled < head.len() {
+            match reader.read(&mut head[filled..]).await? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        head.truncate(filled);
+
+        let format = match (AudioFormat::detect(&head), hint) {
+            (Some(detected), Some(hint)) if detected != hint => {
+                debug!("Detected {:?} audio, instead of {:?}", detected, hint);
+                detected
+            }
+            (Some(detected), _) => detected,
+            (None, Some(hint)) => hint,
+            (None, None) => {
+                return Err(crate::Error::IOError(
+                    "Unable to detect the audio format.".to_string(),
+                ))
+            }
+        };
+
+        let mut stream = Self::new(reader);
+        stream.head = Some(head).filter(|head| !head.is_empty());
+        Ok((stream, format))
+    }
+}
+
+impl<R> Stream for PullAudioInputStream<R>
+where
+    R: AsyncRead + Unpin,
+{
+    type Item = Vec<u8>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(head) = self.head.take() {
+            return Poll::Ready(Some(head));
+        }
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        let mut chunk = vec![0; READ_SIZE];
+        let mut buf = ReadBuf::new(&mut chunk);
+        match Pin::new(&mut self.reader).poll_read(cx, &mut buf) {
+            Poll::Ready(Ok(())) => {
+                let n = buf.filled().len();
+                if n == 0 {
+                    self.done = true;
+                    return Poll::Ready(None);
+                }
+                chunk.truncate(n);
+                Poll::Ready(Some(chunk))
+            }
+            Poll::Ready(Err(e)) => {
+                warn!("Failed to read chunk: {}", e);
+                self.done = true;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_stream::StreamExt;
+
+    #[tokio::test]
+    async fn test_push_stream_yields_written_audio_until_closed() {
+        let (writer, stream) = PushAudioInputStream::new(16, BufferPolicy::Error);
+        writer.write(&[1, 2]).unwrap();
+        writer.write(&[3]).unwrap();
+        writer.close();
+
+        assert!(writer.write(&[4]).is_err());
+        assert_eq!(stream.collect::<Vec<_>>().await, vec![vec![1, 2], vec![3]]);
+    }
+
+    #[tokio::test]
+    async fn test_push_stream_closed_when_last_writer_dropped() {
+        let (writer, stream) = PushAudioInputStream::new(16, BufferPolicy::Error);
+        let other = writer.clone();
+        writer.write(&[1, 2]).unwrap();
+        drop(writer);
+
+        other.write(&[3]).unwrap();
+        assert!(!other.inner.state().closed);
+        drop(other);
+
+        assert_eq!(stream.collect::<Vec<_>>().await, vec![vec![1, 2], vec![3]]);
+    }
+
+    #[tokio::test]
+    async fn test_push_stream_ignores_poisoning() {
+        let (writer, stream) = PushAudioInputStream::new(16, BufferPolicy::Error);
+        let inner = writer.inner.clone();
+        let _ = std::thread::spawn(move || {
+            let _state = inner.state.lock().unwrap();
+            panic!("poison the state");
+        })
+        .join();
+        assert!(stream.inner.state.is_poisoned());
+
+        writer.write(&[1, 2]).unwrap();
+        writer.close();
+        assert_eq!(stream.collect::<Vec<_>>().await, vec![vec![1, 2]]);
+    }
+
+    #[tokio::test]
+    async fn test_push_stream_error_policy() {
+        let (writer, _stream) = PushAudioInputStream::new(4, BufferPolicy::Error);
+        writer.write(&[1, 2, 3]).unwrap();
+        assert!(matches!(
+            writer.write(&[4, 5]),
+            Err(crate::Error::IOError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_push_stream_drop_oldest_policy() {
+        let (writer, stream) = PushAudioInputStream::new(4, BufferPolicy::DropOldest);
+        writer.write(&[1, 2]).unwrap();
+        writer.write(&[3, 4]).unwrap();
+        writer.write(&[5, 6]).unwrap();
+        writer.close();
+
+        assert_eq!(
+            stream.collect::<Vec<_>>().await,
+            vec![vec![3, 4], vec![5, 6]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push_stream_block_policy() {
+        let (writer, stream) = PushAudioInputStream::new(2, BufferPolicy::Block);
+        let handle = std::thread::spawn(move || {
+            for i in 0..4u8 {
+                writer.write(&[i, i]).unwrap();
+            }
+        });
+
+        assert_eq!(
+            stream.collect::<Vec<_>>().await,
+            vec![vec![0, 0], vec![1, 1], vec![2, 2], vec![3, 3]]
+        );
+        handle.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_push_stream_dropped_releases_blocked_writer() {
+        let (writer, stream) = PushAudioInputStream::new(2, BufferPolicy::Block);
+        writer.write(&[1, 2]).unwrap();
+        let handle = std::thread::spawn(move || writer.write(&[3, 4]));
+
+        // Let the writer block on the full buffer.
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        drop(stream);
+
+        assert!(matches!(
+            handle.join().unwrap(),
+            Err(crate::Error::IOError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pull_stream_reads_until_eof() {
+        let data: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+        let stream = PullAudioInputStream::new(data.as_slice());
+
+        let chunks = stream.collect::<Vec<_>>().await;
+        assert_eq!(chunks[0].len(), READ_SIZE);
+        assert_eq!(chunks.concat(), data);
+    }
+
+    #[tokio::test]
+    async fn test_pull_stream_detect_format() {
+        let data = b"fLaC\0\0\0\x22 and the rest of the audio".to_vec();
+
+        let (stream, format) =
+            PullAudioInputStream::detect_format(data.as_slice(), Some(AudioFormat::Mp3))
+                .await
+                .unwrap();
+        assert_eq!(format, AudioFormat::Flac);
+        assert_eq!(stream.collect::<Vec<_>>().await.concat(), data);
+
+        let (_, format) =
+            PullAudioInputStream::detect_format(&b"unknown"[..], Some(AudioFormat::Mp3))
+                .await
+                .unwrap();
+        assert_eq!(format, AudioFormat::Mp3);
+
+        assert!(PullAudioInputStream::detect_format(&b""[..], None)
+            .await
+            .is_err());
+    }
+}
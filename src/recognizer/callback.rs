@@ -1,16 +1,19 @@
 use crate::callback::{BoxFuture, OnError, OnSessionEnded, OnSessionStarted};
-use crate::recognizer::{Duration, Event, Offset, RawMessage, Recognized};
+use crate::recognizer::{
+    Event, LanguageDetectedEvent, RecognizedEvent, RecognizingDeltaEvent, SpeechDetectedEvent,
+    UnMatchEvent,
+};
 use crate::RequestId;
 use std::future::Future;
 use std::sync::Arc;
 
-pub(crate) type OnRecognizing =
-    Box<dyn Fn(RequestId, Recognized, Offset, Duration, RawMessage) -> BoxFuture>;
-pub(crate) type OnRecognized =
-    Box<dyn Fn(RequestId, Recognized, Offset, Duration, RawMessage) -> BoxFuture>;
-pub(crate) type OnUnMatch = Box<dyn Fn(RequestId, Offset, Duration, RawMessage) -> BoxFuture>;
-pub(crate) type OnStartDetected = Box<dyn Fn(RequestId, Offset) -> BoxFuture>;
-pub(crate) type OnEndDetected = Box<dyn Fn(RequestId, Offset) -> BoxFuture>;
+pub(crate) type OnRecognizing = Box<dyn Fn(RecognizedEvent) -> BoxFuture>;
+pub(crate) type OnRecognizingDelta = Box<dyn Fn(RecognizingDeltaEvent) -> BoxFuture>;
+pub(crate) type OnRecognized = Box<dyn Fn(RecognizedEvent) -> BoxFuture>;
+pub(crate) type OnLanguageDetected = Box<dyn Fn(LanguageDetectedEvent) -> BoxFuture>;
+pub(crate) type OnUnMatch = Box<dyn Fn(UnMatchEvent) -> BoxFuture>;
+pub(crate) type OnStartDetected = Box<dyn Fn(SpeechDetectedEvent) -> BoxFuture>;
+pub(crate) type OnEndDetected = Box<dyn Fn(SpeechDetectedEvent) -> BoxFuture>;
 
 #[derive(Default, Clone)]
 pub struct Callback {
@@ -19,7 +22,9 @@ pub struct Callback {
     pub(crate) on_session_ended: Option<Arc<OnSessionEnded>>,
 
     pub(crate) on_recognizing: Option<Arc<OnRecognizing>>,
+    pub(crate) on_recognizing_delta: Option<Arc<OnRecognizingDelta>>,
     pub(crate) on_recognized: Option<Arc<OnRecognized>>,
+    pub(crate) on_language_detected: Option<Arc<OnLanguageDetected>>,
     pub(crate) on_un_match: Option<Arc<OnUnMatch>>,
     pub(crate) on_start_detected: Option<Arc<OnStartDetected>>,
     pub(crate) on_end_detected: Option<Arc<OnEndDetected>>,
@@ -57,62 +62,64 @@ impl Callback {
 
     pub fn on_recognizing<F, Fut>(mut self, func: F) -> Self
     where
-        F: Fn(RequestId, Recognized, Offset, Duration, RawMessage) -> Fut + Send + Sync + 'static,
+        F: Fn(RecognizedEvent) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
-        self.on_recognizing = Some(Arc::new(Box::new(
-            move |request_id, recognized, offset, duration, raw_message| {
-                Box::pin(func(request_id, recognized, offset, duration, raw_message))
-            },
-        )));
+        self.on_recognizing = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
+        self
+    }
+
+    pub fn on_recognizing_delta<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(RecognizingDeltaEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_recognizing_delta = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
         self
     }
 
     pub fn on_recognized<F, Fut>(mut self, func: F) -> Self
     where
-        F: Fn(RequestId, Recognized, Offset, Duration, RawMessage) -> Fut + Send + Sync + 'static,
+        F: Fn(RecognizedEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_recognized = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
+        self
+    }
+
+    pub fn on_language_detected<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(LanguageDetectedEvent) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
-        self.on_recognized = Some(Arc::new(Box::new(
-            move |request_id, recognized, offset, duration, raw_message| {
-                Box::pin(func(request_id, recognized, offset, duration, raw_message))
-            },
-        )));
+        self.on_language_detected = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
         self
     }
 
     pub fn on_un_match<F, Fut>(mut self, func: F) -> Self
     where
-        F: Fn(RequestId, Offset, Duration, RawMessage) -> Fut + Send + Sync + 'static,
+        F: Fn(UnMatchEvent) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
-        self.on_un_match = Some(Arc::new(Box::new(
-            move |request_id, offset, duration, raw_message| {
-                Box::pin(func(request_id, offset, duration, raw_message))
-            },
-        )));
+        self.on_un_match = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
         self
     }
 
     pub fn on_start_detected<F, Fut>(mut self, func: F) -> Self
     where
-        F: Fn(RequestId, Offset) -> Fut + Send + Sync + 'static,
+        F: Fn(SpeechDetectedEvent) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
-        self.on_start_detected = Some(Arc::new(Box::new(move |request, offset| {
-            Box::pin(func(request, offset))
-        })));
+        self.on_start_detected = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
         self
     }
 
     pub fn on_end_detected<F, Fut>(mut self, func: F) -> Self
     where
-        F: Fn(RequestId, Offset) -> Fut + Send + Sync + 'static,
+        F: Fn(SpeechDetectedEvent) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
-        self.on_end_detected = Some(Arc::new(Box::new(move |request, offset| {
-            Box::pin(func(request, offset))
-        })));
+        self.on_end_detected = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
         self
     }
 }
@@ -125,60 +132,58 @@ impl crate::callback::Callback for Callback {
     fn on_event(&self, item: Self::Item) -> impl Future<Output = ()> {
         async move {
             match &item {
-                Ok(Event::SessionStarted(request_id)) => {
+                Ok(Event::SessionStarted(event)) => {
                     tracing::debug!("Session started");
                     if let Some(f) = self.on_session_started.as_ref() {
-                        f(*request_id).await
+                        f(event.request_id()).await
                     }
                 }
-                Ok(Event::SessionEnded(request_id)) => {
+                Ok(Event::SessionEnded(event)) => {
                     tracing::debug!("Session ended");
                     if let Some(f) = self.on_session_ended.as_ref() {
-                        f(*request_id).await
+                        f(event.request_id()).await
                     }
                 }
 
-                Ok(Event::Recognizing(request_id, recognized, offset, duration, raw)) => {
+                Ok(Event::Recognizing(event)) => {
                     if let Some(f) = self.on_recognizing.as_ref() {
-                        f(
-                            *request_id,
-                            recognized.clone(),
-                            *offset,
-                            *duration,
-                            raw.clone(),
-                        )
-                        .await
+                        f(event.clone()).await
+                    }
+                }
+
+                Ok(Event::RecognizingDelta(event)) => {
+                    if let Some(f) = self.on_recognizing_delta.as_ref() {
+                        f(event.clone()).await
                     }
                 }
 
-                Ok(Event::Recognized(request_id, recognized, offset, duration, raw)) => {
+                Ok(Event::Recognized(event)) => {
                     if let Some(f) = self.on_recognized.as_ref() {
-                        f(
-                            *request_id,
-                            recognized.clone(),
-                            *offset,
-                            *duration,
-                            raw.clone(),
-                        )
-                        .await
+                        f(event.clone()).await
+                    }
+                }
+
+                Ok(Event::LanguageDetected(event)) => {
+                    if let Some(f) = self.on_language_detected.as_ref() {
+                        f(event.clone()).await
                     }
                 }
 
-                Ok(Event::UnMatch(request_id, offset, duration, raw)) => {
+                Ok(Event::UnMatch(event)) => {
                     if let Some(f) = self.on_un_match.as_ref() {
-                        f(*request_id, *offset, *duration, raw.clone()).await
+                        f(event.clone()).await
                     }
                 }
 
-                Ok(Event::EndDetected(request_id, offset)) => {
+                Ok(Event::EndDetected(event)) => {
                     if let Some(f) = self.on_end_detected.as_ref() {
-                        f(*request_id, *offset).await
+                        f(event.clone()).await
                     }
                 }
 
-                Ok(Event::StartDetected(request_id, offset)) => {
+                Ok(Event::StartDetected(event)) => {
                     if let Some(f) = self.on_start_detected.as_ref() {
-                        f(*request_id, *offset).await
+                        f(event.clone()).await
                     }
                 }
 
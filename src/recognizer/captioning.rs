@@ -0,0 +1,563 @@
+use crate::recognizer::{CaptionOptions, Event, LineSplit, Offset};
+use crate::Ticks;
+use std::collections::{HashMap, VecDeque};
+use tokio_stream::{Stream, StreamExt};
+
+/// The configuration of the live captioning.
+///
+/// The `Recognizing` hypotheses keep changing their last words. A word is shown only when
+/// the last hypotheses agree on it, or when it waits for longer than the delay budget.
+/// Shown words are never taken back, until the final result of the phrase replaces them.
+///
+/// Set [`Config::set_stable_partial_result_threshold`](crate::recognizer::Config::set_stable_partial_result_threshold)
+/// too, to get more stable hypotheses from the service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Captioning {
+    pub(crate) stability: usize,
+    pub(crate) delay: std::time::Duration,
+    pub(crate) layout: CaptionOptions,
+}
+
+impl Default for Captioning {
+    fn default() -> Self {
+        Self {
+            stability: 3,
+            delay: std::time::Duration::from_secs(3),
+            layout: CaptionOptions::default(),
+        }
+    }
+}
+
+impl Captioning {
+    /// Create the configuration with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of consecutive hypotheses that must agree on a word to show it. Default: 3.
+    pub fn with_stability(mut self, stability: usize) -> Self {
+        self.stability = stability.max(1);
+        self
+    }
+
+    /// Set the maximum time, in audio, a word waits before being shown. Default: 3 seconds.
+    ///
+    /// Past the delay, all the words of the hypothesis but the last one are shown.
+    pub fn with_delay(mut self, delay: std::time::Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Set the maximum number of lines, and of characters per line, of the frames.
+    ///
+    /// Default: 2 lines of 42 characters.
+    pub fn with_layout(mut self, layout: CaptionOptions) -> Self {
+        self.layout = layout;
+        self
+    }
+}
+
+/// The caption to display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaptionFrame {
+    /// The lines to display, the most recent text last.
+    pub lines: Vec<String>,
+    /// The position in the audio up to which the text was heard, in ticks of 100 nanoseconds.
+    ///
+    /// Display the frame at this position plus the latency of the broadcast.
+    pub offset: Offset,
+    /// Whether the frame shows the final text of the phrase.
+    pub is_final: bool,
+    /// The audio channel of the frame, when the channels are recognized separately.
+    ///
+    /// Each channel has its own lines.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub channel: Option<u16>,
+}
+
+/// Turns the recognizer events into stable caption frames.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example(client: azure_speech::recognizer::Client) -> azure_speech::Result<()> {
+/// use azure_speech::recognizer::{Captioner, Captioning};
+/// use azure_speech::stream::StreamExt;
+///
+/// let events = client.recognize_file("audio.wav").await?;
+/// let mut frames = std::pin::pin!(Captioner::new(Captioning::default()).captions(events));
+///
+/// while let Some(frame) = frames.next().await {
+///     println!("{}", frame?.lines.join("\n"));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Captioner {
+    config: Captioning,
+    /// The screen of each audio channel.
+    screens: HashMap<Option<u16>, Screen>,
+}
+
+/// The text on screen of an audio channel.
+#[derive(Debug, Clone, Default)]
+struct Screen {
+    /// The text of the completed phrases still on screen.
+    history: VecDeque<Vec<String>>,
+    /// The words of the current phrase already shown.
+    shown: Vec<String>,
+    /// The last hypotheses of the current phrase.
+    hypotheses: VecDeque<Vec<String>>,
+    /// The position in the audio since which the hypothesis has words not shown yet.
+    pending_since: Option<Offset>,
+    lines: Vec<String>,
+}
+
+impl Captioner {
+    /// Create the captioner.
+    pub fn new(config: Captioning) -> Self {
+        Self {
+            config,
+            screens: HashMap::new(),
+        }
+    }
+
+    /// Turn the stream of events into a stream of caption frames.
+    ///
+    /// The errors of the events are forwarded.
+    pub fn captions<S>(mut self, stream: S) -> impl Stream<Item = crate::Result<CaptionFrame>>
+    where
+        S: Stream<Item = crate::Result<Event>>,
+    {
+        stream.filter_map(move |event| match event {
+            Ok(event) => self.push(&event).map(Ok),
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// Process the event.
+    ///
+    /// Returns the new frame to display, if the displayed text changed.
+    /// The channels of a split stream have their own screens.
+    pub fn push(&mut self, event: &Event) -> Option<CaptionFrame> {
+        let channel = event.channel();
+        self.screens
+            .entry(channel)
+            .or_default()
+            .push(&self.config, event)
+            .map(|frame| CaptionFrame { channel, ..frame })
+    }
+}
+
+impl Screen {
+    fn push(&mut self, config: &Captioning, event: &Event) -> Option<CaptionFrame> {
+        match event {
+            Event::Recognizing(event) => {
+                let end = event.offset() + event.duration();
+                let words = config.split(&event.result().text);
+                self.stabilize(config, words, end)
+                    .then(|| self.frame(config, end, false))
+                    .flatten()
+            }
+            Event::Recognized(event) => {
+                let end = event.offset() + event.duration();
+                let words = config.split(&event.result().text);
+                self.complete_phrase(config, words);
+                let frame = self.frame(config, end, true);
+                // The final text is always notified, even when it was already shown.
+                frame.or_else(|| {
+                    (!self.lines.is_empty() && !event.result().text.trim().is_empty()).then(|| {
+                        CaptionFrame {
+                            lines: self.lines.clone(),
+                            offset: end,
+                            is_final: true,
+                            channel: None,
+                        }
+                    })
+                })
+            }
+            Event::UnMatch(event) => {
+                // The shown words cannot be taken back: they stay on screen.
+                let shown = std::mem::take(&mut self.shown);
+                self.complete_phrase(config, shown);
+                self.frame(config, event.offset() + event.duration(), true)
+            }
+            _ => None,
+        }
+    }
+
+    /// Add the hypothesis, and extend the shown words with the stable ones.
+    ///
+    /// Returns whether new words are shown.
+    fn stabilize(&mut self, config: &Captioning, words: Vec<String>, now: Offset) -> bool {
+        self.hypotheses.push_back(words);
+        while self.hypotheses.len() > config.stability {
+            self.hypotheses.pop_front();
+        }
+        let latest = &self.hypotheses[self.hypotheses.len() - 1];
+
+        let mut stable = if self.hypotheses.len() == config.stability {
+            common_prefix_len(&self.hypotheses)
+        } else {
+            0
+        };
+
+        if latest.len() <= self.shown.len() {
+            self.pending_since = None;
+            return false;
+        }
+        let since = *self.pending_since.get_or_insert(now);
+        let delay = Ticks::from(config.delay);
+        if now.saturating_sub(since) >= delay {
+            stable = stable.max(latest.len() - 1);
+        }
+
+        // The hypothesis must still agree with the shown words.
+        if stable <= self.shown.len() || latest[..self.shown.len()] != self.shown[..] {
+            return false;
+        }
+
+        self.shown = latest[..stable].to_vec();
+        self.pending_since = (latest.len() > stable).then_some(now);
+        true
+    }
+
+    /// Move the phrase to the history, and keep only the phrases still on screen.
+    fn complete_phrase(&mut self, config: &Captioning, words: Vec<String>) {
+        self.shown.clear();
+        self.hypotheses.clear();
+        self.pending_since = None;
+
+        if words.is_empty() {
+            return;
+        }
+        self.history.push_back(words);
+        while self.history.len() > 1 {
+            let rest: Vec<String> = self.history.iter().skip(1).flatten().cloned().collect();
+            if config.wrap(&rest).len() < config.layout.max_lines {
+                break;
+            }
+            self.history.pop_front();
+        }
+    }
+
+    /// Lay out the text on screen, and return the frame if it changed.
+    fn frame(
+        &mut self,
+        config: &Captioning,
+        offset: Offset,
+        is_final: bool,
+    ) -> Option<CaptionFrame> {
+        let words: Vec<String> = self
+            .history
+            .iter()
+            .flatten()
+            .chain(self.shown.iter())
+            .cloned()
+            .collect();
+        let mut lines = config.wrap(&words);
+        let hidden = lines.len().saturating_sub(config.layout.max_lines);
+        lines.drain(..hidden);
+
+        if lines == self.lines {
+            return None;
+        }
+        self.lines = lines.clone();
+        Some(CaptionFrame {
+            lines,
+            offset,
+            is_final,
+            channel: None,
+        })
+    }
+}
+
+impl Captioning {
+    fn split(&self, text: &str) -> Vec<String> {
+        match self.layout.line_split {
+            LineSplit::Characters => text
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(String::from)
+                .collect(),
+            _ => text.split_whitespace().map(String::from).collect(),
+        }
+    }
+
+    fn wrap(&self, words: &[String]) -> Vec<String> {
+        let separator = match self.layout.line_split {
+            LineSplit::Characters => "",
+            _ => " ",
+        };
+
+        let mut lines = vec![];
+        let mut line = String::new();
+        for word in words {
+            let length = line.chars().count() + separator.len() + word.chars().count();
+            if !line.is_empty() && length > self.layout.max_line_length {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push_str(separator);
+            }
+            line.push_str(word);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+/// The number of words at the beginning of all the hypotheses.
+fn common_prefix_len(hypotheses: &VecDeque<Vec<String>>) -> usize {
+    let shortest = hypotheses.iter().map(Vec::len).min().unwrap_or(0);
+    (0..shortest)
+        .take_while(|&i| hypotheses.iter().all(|h| h[i] == hypotheses[0][i]))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::{
+        NoMatchReason, Recognized, RecognizedEvent, SessionEvent, UnMatchEvent,
+    };
+    use uuid::Uuid;
+
+    const SECOND: Ticks = Ticks::from_millis(1000);
+
+    fn recognized(text: &str) -> Recognized {
+        Recognized {
+            text: text.to_string(),
+            primary_language: None,
+            speaker_id: None,
+            pronunciation_assessment: None,
+        }
+    }
+
+    fn recognizing(text: &str, end: Offset) -> Event {
+        Event::Recognizing(RecognizedEvent::new(
+            Uuid::nil(),
+            recognized(text),
+            Ticks::ZERO,
+            end,
+            String::new(),
+        ))
+    }
+
+    fn final_result(text: &str, end: Offset) -> Event {
+        Event::Recognized(RecognizedEvent::new(
+            Uuid::nil(),
+            recognized(text),
+            Ticks::ZERO,
+            end,
+            String::new(),
+        ))
+    }
+
+    fn lines(frame: Option<CaptionFrame>) -> Option<Vec<String>> {
+        frame.map(|f| f.lines)
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_frame_serde_round_trip() {
+        let frame = CaptionFrame {
+            lines: vec!["hello".to_string(), "world".to_string()],
+            offset: SECOND,
+            is_final: true,
+            channel: Some(1),
+        };
+
+        let json = serde_json::to_string(&frame).unwrap();
+        assert_eq!(serde_json::from_str::<CaptionFrame>(&json).unwrap(), frame);
+    }
+
+    #[test]
+    fn test_shows_words_once_stable() {
+        let mut captioner = Captioner::new(Captioning::new().with_stability(2));
+
+        assert_eq!(lines(captioner.push(&recognizing("hello", SECOND))), None);
+        assert_eq!(
+            lines(captioner.push(&recognizing("hello word", SECOND))),
+            Some(vec!["hello".to_string()])
+        );
+        // "word" is revised to "world": it was never shown.
+        assert_eq!(
+            lines(captioner.push(&recognizing("hello world how", SECOND))),
+            None
+        );
+        assert_eq!(
+            lines(captioner.push(&recognizing("hello world how are", SECOND))),
+            Some(vec!["hello world how".to_string()])
+        );
+
+        let frame = captioner
+            .push(&final_result("Hello world, how are you?", SECOND))
+            .unwrap();
+        assert!(frame.is_final);
+        assert_eq!(frame.lines, vec!["Hello world, how are you?"]);
+    }
+
+    #[test]
+    fn test_shown_words_are_not_taken_back() {
+        let mut captioner = Captioner::new(Captioning::new().with_stability(2));
+
+        captioner.push(&recognizing("the cat", SECOND));
+        captioner.push(&recognizing("the cat sat", SECOND));
+        assert_eq!(captioner.screens[&None].lines, vec!["the cat"]);
+
+        // The service revises the shown words: nothing changes until they agree again.
+        assert_eq!(captioner.push(&recognizing("the hat sat", SECOND)), None);
+        assert_eq!(captioner.push(&recognizing("the hat sat on", SECOND)), None);
+        assert_eq!(
+            lines(captioner.push(&recognizing("the cat sat on the", SECOND))),
+            None
+        );
+        assert_eq!(
+            lines(captioner.push(&recognizing("the cat sat on the mat", SECOND))),
+            Some(vec!["the cat sat on the".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_delay_budget_forces_words() {
+        let mut captioner = Captioner::new(
+            Captioning::new()
+                .with_stability(100)
+                .with_delay(std::time::Duration::from_secs(2)),
+        );
+
+        assert_eq!(captioner.push(&recognizing("one two", SECOND)), None);
+        assert_eq!(
+            captioner.push(&recognizing("one two three", SECOND * 2)),
+            None
+        );
+
+        let frame = captioner
+            .push(&recognizing("one two three four", SECOND * 3))
+            .unwrap();
+        assert_eq!(frame.lines, vec!["one two three"]);
+        assert_eq!(frame.offset, SECOND * 3);
+        assert!(!frame.is_final);
+    }
+
+    #[test]
+    fn test_channels_have_their_own_screens() {
+        let mut captioner = Captioner::new(Captioning::new().with_stability(1));
+
+        let left = captioner
+            .push(&recognizing("hello there", SECOND).with_channel(0))
+            .unwrap();
+        assert_eq!(left.channel, Some(0));
+        assert_eq!(left.lines, vec!["hello there"]);
+
+        // The phrase of the other channel does not complete the phrase of the first one.
+        let right = captioner
+            .push(&final_result("Good morning.", SECOND).with_channel(1))
+            .unwrap();
+        assert_eq!(right.channel, Some(1));
+        assert_eq!(right.lines, vec!["Good morning."]);
+
+        let left = captioner
+            .push(&recognizing("hello there how", SECOND).with_channel(0))
+            .unwrap();
+        assert_eq!(left.lines, vec!["hello there how"]);
+    }
+
+    #[test]
+    fn test_frames_are_bounded() {
+        let mut captioner = Captioner::new(
+            Captioning::new().with_stability(1).with_layout(
+                CaptionOptions::default()
+                    .with_max_line_length(12)
+                    .with_max_lines(2),
+            ),
+        );
+
+        captioner.push(&final_result("The first phrase.", SECOND));
+        captioner.push(&final_result("The second phrase.", SECOND * 2));
+        let frame = captioner
+            .push(&recognizing("and the third", SECOND * 3))
+            .unwrap();
+
+        assert_eq!(frame.lines, vec!["phrase. and", "the third"]);
+        assert_eq!(captioner.screens[&None].history.len(), 1);
+    }
+
+    #[test]
+    fn test_final_result_is_notified_when_unchanged() {
+        let mut captioner = Captioner::new(Captioning::new().with_stability(1));
+
+        captioner.push(&recognizing("hello", SECOND));
+        let frame = captioner.push(&final_result("hello", SECOND)).unwrap();
+
+        assert!(frame.is_final);
+        assert_eq!(frame.lines, vec!["hello"]);
+        assert_eq!(captioner.push(&final_result("", SECOND * 2)), None);
+    }
+
+    #[test]
+    fn test_unmatch_keeps_shown_words() {
+        let mut captioner = Captioner::new(Captioning::new().with_stability(1));
+
+        captioner.push(&recognizing("uh huh", SECOND));
+        assert_eq!(
+            captioner.push(&Event::UnMatch(UnMatchEvent::new(
+                Uuid::nil(),
+                NoMatchReason::NotRecognized,
+                Ticks::ZERO,
+                SECOND,
+                String::new()
+            ))),
+            None
+        );
+        assert_eq!(
+            lines(captioner.push(&recognizing("okay", SECOND * 2))),
+            Some(vec!["uh huh okay".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_characters_layout() {
+        let mut captioner = Captioner::new(
+            Captioning::new().with_stability(1).with_layout(
+                CaptionOptions::default()
+                    .with_line_split(LineSplit::Characters)
+                    .with_max_line_length(4),
+            ),
+        );
+
+        assert_eq!(
+            lines(captioner.push(&recognizing("你好世界再见", SECOND))),
+            Some(vec!["你好世界".to_string(), "再见".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_captions_stream() {
+        let events = vec![
+            Ok(Event::SessionStarted(SessionEvent::new(Uuid::nil()))),
+            Ok(recognizing("hello", SECOND)),
+            Ok(recognizing("hello world", SECOND)),
+            Ok(final_result("Hello world.", SECOND)),
+            Err(crate::Error::RuntimeError("failed".to_string())),
+        ];
+
+        let frames: Vec<_> = Captioner::new(Captioning::new().with_stability(2))
+            .captions(tokio_stream::iter(events))
+            .collect()
+            .await;
+
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].as_ref().unwrap().lines, vec!["hello"]);
+        assert_eq!(frames[1].as_ref().unwrap().lines, vec!["Hello world."]);
+        assert!(frames[2].is_err());
+    }
+}
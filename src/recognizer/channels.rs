@@ -0,0 +1,336 @@
+use crate::recognizer::{Event, Offset, WavSpec};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::collections::VecDeque;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+
+/// How the channels of multi-channel PCM audio are recognized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChannelMode {
+    /// The audio is sent as it is, and the service recognizes the channels together.
+    #[default]
+    Mixed,
+    /// Each channel is recognized separately, in its own session on its own connection.
+    ///
+    /// The events of all the channels are merged in a single stream, ordered by offset, and
+    /// tagged with the index of their channel. An event is only emitted once every channel
+    /// has an event or has ended, so a silent channel delays the events of the others: they
+    /// are kept in memory meanwhile.
+    /// A channel whose connection is lost for good ends with the error, and the other channels
+    /// keep being recognized.
+    /// Only the `Wav` and `RawPcm` formats can be split.
+    Split,
+}
+
+/// Splits the interleaved frames of PCM audio into one buffer per channel.
+///
+/// The incomplete frame at the end of a chunk is kept for the next one.
+#[derive(Debug)]
+pub(crate) struct Deinterleaver {
+    channels: usize,
+    sample_size: usize,
+    pending: Vec<u8>,
+}
+
+impl Deinterleaver {
+    pub(crate) fn new(spec: &WavSpec) -> Self {
+        let channels = spec.channels.max(1) as usize;
+        Self {
+            channels,
+            sample_size: (spec.block_align() as usize / channels).max(1),
+            pending: vec![],
+        }
+    }
+
+    /// The audio of each channel, for the complete frames received so far.
+    pub(crate) fn process(&mut self, data: &[u8]) -> Vec<Vec<u8>> {
+        self.pending.extend_from_slice(data);
+        let frame_size = self.sample_size * self.channels;
+        let complete = self.pending.len() - self.pending.len() % frame_size;
+
+        let mut channels = vec![Vec::with_capacity(complete / self.channels); self.channels];
+        for frame in self.pending[..complete].chunks_exact(frame_size) {
+            for (channel, sample) in channels
+                .iter_mut()
+                .zip(frame.chunks_exact(self.sample_size))
+            {
+                channel.extend_from_slice(sample);
+            }
+        }
+        self.pending.drain(..complete);
+        channels
+    }
+}
+
+/// Spawn the task that splits the audio, and return the mono WAV stream of each channel.
+///
+/// The `buffered` bytes are the audio already read after the header.
+pub(crate) fn split_channels<A>(
+    mut audio: A,
+    buffered: Vec<u8>,
+    spec: WavSpec,
+) -> Vec<ReceiverStream<Vec<u8>>>
+where
+    A: Stream<Item = Vec<u8>> + Send + Unpin + 'static,
+{
+    let header = WavSpec {
+        channels: 1,
+        ..spec
+    }
+    .to_header();
+    let mut deinterleaver = Deinterleaver::new(&spec);
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..deinterleaver.channels)
+        .map(|_| mpsc::channel::<Vec<u8>>(16))
+        .unzip();
+
+    tokio::spawn(async move {
+        let mut senders: Vec<_> = senders.into_iter().map(Some).collect();
+        let mut chunk = Some(buffered);
+        // The header of the mono audio is the first chunk of each channel.
+        let mut channels = vec![header; senders.len()];
+        loop {
+            for (sender, data) in senders.iter_mut().zip(channels) {
+                if data.is_empty() {
+                    continue;
+                }
+                // The recognition of the channel ended: its audio is dropped.
+                if let Some(s) = sender.as_ref() {
+                    if s.send(data).await.is_err() {
+                        sender.take();
+                    }
+                }
+            }
+            if senders.iter().all(Option::is_none) {
+                break;
+            }
+
+            let data = match chunk.take() {
+                Some(data) => data,
+                None => match audio.next().await {
+                    Some(data) => data,
+                    None => break,
+                },
+            };
+            channels = deinterleaver.process(&data);
+        }
+    });
+
+    receivers.into_iter().map(ReceiverStream::new).collect()
+}
+
+struct ChannelEvents<S> {
+    events: Pin<Box<S>>,
+    /// The events received and not emitted yet, with the offset used to order them.
+    queue: VecDeque<(Offset, crate::Result<Event>)>,
+    /// The offset of the last event of the channel.
+    offset: Offset,
+    ended: bool,
+}
+
+/// The events of the channels, merged in the order of their offsets.
+///
+/// The events without an offset, and the errors, keep the offset of the previous event of
+/// their channel. On equal offsets, the events of the lowest channel come first.
+///
+/// Every channel is read as soon as its events are available, even while they wait for the
+/// events of the other channels: the connection of a channel drops the events not read in time.
+pub(crate) struct MergedEvents<S> {
+    channels: Vec<ChannelEvents<S>>,
+}
+
+impl<S> MergedEvents<S>
+where
+    S: Stream<Item = crate::Result<Event>>,
+{
+    pub(crate) fn new(channels: Vec<S>) -> Self {
+        Self {
+            channels: channels
+                .into_iter()
+                .map(|events| ChannelEvents {
+                    events: Box::pin(events),
+                    queue: VecDeque::new(),
+                    offset: Offset::ZERO,
+                    ended: false,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<S> Stream for MergedEvents<S>
+where
+    S: Stream<Item = crate::Result<Event>>,
+{
+    type Item = crate::Result<Event>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        for channel in self.channels.iter_mut() {
+            while !channel.ended {
+                match channel.events.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(event)) => {
+                        if let Some(offset) = event.as_ref().ok().and_then(Event::offset) {
+                            channel.offset = channel.offset.max(offset);
+                        }
+                        channel.queue.push_back((channel.offset, event));
+                    }
+                    Poll::Ready(None) => channel.ended = true,
+                    Poll::Pending => break,
+                }
+            }
+        }
+        if self
+            .channels
+            .iter()
+            .any(|channel| channel.queue.is_empty() && !channel.ended)
+        {
+            return Poll::Pending;
+        }
+
+        let next = self
+            .channels
+            .iter()
+            .enumerate()
+            .filter_map(|(index, channel)| {
+                channel.queue.front().map(|(offset, _)| (*offset, index))
+            })
+            .min();
+        let Some((_, index)) = next else {
+            return Poll::Ready(None);
+        };
+        let (_, event) = self.channels[index]
+            .queue
+            .pop_front()
+            .expect("the next event of the channel");
+        Poll::Ready(Some(event.map(|event| event.with_channel(index as u16))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::{SampleFormat, SessionEvent, SpeechDetectedEvent};
+    use crate::RequestId;
+
+    fn spec(channels: u16, bits_per_sample: u16) -> WavSpec {
+        WavSpec {
+            sample_rate: 16000,
+            bits_per_sample,
+            channels,
+            sample_format: SampleFormat::Int,
+        }
+    }
+
+    #[test]
+    fn test_deinterleave_keeps_partial_frames() {
+        let mut deinterleaver = Deinterleaver::new(&spec(2, 16));
+
+        assert_eq!(
+            deinterleaver.process(&[1, 1, 2, 2, 3]),
+            vec![vec![1, 1], vec![2, 2]]
+        );
+        assert_eq!(
+            deinterleaver.process(&[3, 4, 4, 5, 5, 6, 6]),
+            vec![vec![3, 3, 5, 5], vec![4, 4, 6, 6]]
+        );
+        assert_eq!(deinterleaver.process(&[7]), vec![Vec::<u8>::new(); 2]);
+    }
+
+    #[test]
+    fn test_deinterleave_three_channels() {
+        let mut deinterleaver = Deinterleaver::new(&spec(3, 8));
+
+        assert_eq!(
+            deinterleaver.process(&[1, 2, 3, 1, 2, 3]),
+            vec![vec![1, 1], vec![2, 2], vec![3, 3]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_split_channels() {
+        let audio = tokio_stream::iter(vec![vec![2, 2, 3], vec![3, 4, 4, 5, 5]]);
+        let channels = split_channels(audio, vec![0, 0, 1, 1], spec(2, 16));
+        let mut audio = vec![];
+        for channel in channels {
+            audio.push(channel.collect::<Vec<_>>().await.concat());
+        }
+
+        let header = spec(1, 16).to_header();
+        assert_eq!(audio[0], [&header[..], &[0, 0, 2, 2, 4, 4]].concat());
+        assert_eq!(audio[1], [&header[..], &[1, 1, 3, 3, 5, 5]].concat());
+    }
+
+    #[tokio::test]
+    async fn test_merge_in_offset_order() {
+        let request_id = RequestId::new_v4();
+        let detected = |offset: u64| {
+            Ok(Event::StartDetected(SpeechDetectedEvent::new(
+                request_id,
+                Offset::new(offset),
+            )))
+        };
+        let started = || Ok(Event::SessionStarted(SessionEvent::new(request_id)));
+        let ended = || Ok(Event::SessionEnded(SessionEvent::new(request_id)));
+
+        let left = tokio_stream::iter(vec![started(), detected(10), detected(40), ended()]);
+        let right = tokio_stream::iter(vec![started(), detected(5), detected(40), ended()]);
+        let events: Vec<_> = MergedEvents::new(vec![left, right])
+            .map(|event| {
+                let event = event.unwrap();
+                let offset = match &event {
+                    Event::StartDetected(event) => Some(event.offset().as_ticks()),
+                    _ => None,
+                };
+                (event.channel(), offset)
+            })
+            .collect()
+            .await;
+
+        assert_eq!(
+            events,
+            vec![
+                (Some(0), None),
+                (Some(1), None),
+                (Some(1), Some(5)),
+                (Some(0), Some(10)),
+                (Some(0), Some(40)),
+                (Some(0), None),
+                (Some(1), Some(40)),
+                (Some(1), None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_reads_the_channels_while_one_is_silent() {
+        let request_id = RequestId::new_v4();
+        let (left_tx, left_rx) = mpsc::channel(32);
+        let (right_tx, right_rx) = mpsc::channel(32);
+
+        // The left channel sends more events than its buffer holds, before the right one speaks.
+        tokio::spawn(async move {
+            for offset in 1..=40 {
+                let event =
+                    Event::StartDetected(SpeechDetectedEvent::new(request_id, Offset::new(offset)));
+                left_tx.send(Ok(event)).await.unwrap();
+            }
+            let event = Event::StartDetected(SpeechDetectedEvent::new(request_id, Offset::ZERO));
+            right_tx.send(Ok(event)).await.unwrap();
+        });
+
+        let events = MergedEvents::new(vec![
+            ReceiverStream::new(left_rx),
+            ReceiverStream::new(right_rx),
+        ])
+        .map(|event| event.unwrap().channel())
+        .collect::<Vec<_>>();
+        let events = tokio::time::timeout(std::time::Duration::from_secs(1), events)
+            .await
+            .expect("the merged events");
+
+        assert_eq!(events.len(), 41);
+        assert_eq!(events[0], Some(1));
+        assert!(events[1..].iter().all(|channel| *channel == Some(0)));
+    }
+}
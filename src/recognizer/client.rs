@@ -1,18 +1,28 @@
 use crate::connector::Client as BaseClient;
+use crate::recognizer::audio_converter::{AudioConverter, TARGET_SPEC};
 use crate::recognizer::audio_format::AudioFormat;
+use crate::recognizer::channels::{split_channels, MergedEvents};
+use crate::recognizer::pacing::Pacer;
+use crate::recognizer::replay::ReplayBuffer;
 use crate::recognizer::session::Session;
 use crate::recognizer::utils::{
     create_audio_header_message, create_audio_message, create_speech_config_message,
     create_speech_context_message,
 };
+use crate::recognizer::voice_activity::VoiceActivityDetector;
+use crate::recognizer::wav::parse_wav_header;
 use crate::recognizer::{
-    AudioDevice, Confidence, Config, Event, OutputFormat, PrimaryLanguage, Recognized,
+    AudioDevice, ChannelMode, Confidence, Config, Event, LanguageDetectedEvent, NoMatchReason,
+    OutputFormat, PrimaryLanguage, PronunciationAssessment, PullAudioInputStream, Recognition,
+    RecognitionHandle, RecognitionMode, RecognizeOnceResult, Recognized, RecognizedEvent,
+    SampleFormat, SessionEvent, SpeechDetectedEvent, UnMatchEvent, WavSpec,
 };
 use crate::utils::get_azure_hostname_from_region;
 use crate::{stream_ext::StreamExt, Auth, Data, Message};
+use futures_util::future::Either;
 use std::cmp::min;
-use tokio::io::AsyncReadExt;
-use tokio_stream::wrappers::ReceiverStream;
+use tokio::io::AsyncRead;
+use tokio::time::Instant;
 use tokio_stream::{Stream, StreamExt as _};
 use tracing::{debug, warn};
 use url::Url;
@@ -43,6 +53,8 @@ impl Client {
             .languages
             .first()
             .ok_or_else(|| crate::Error::IOError("No language specified.".to_string()))?;
+        config.validate_custom_models()?;
+        config.validate_result_filter()?;
         url.query_pairs_mut()
             .append_pair("language", language.to_string().as_str())
             .append_pair("format", config.output_format.as_str())
@@ -52,9 +64,16 @@ impl Client {
             url.query_pairs_mut()
                 .append_pair("wordLevelTimestamps", "true");
         }
-        if config.languages.len() > 1 {
+        if config.is_language_detection_enabled() {
             url.query_pairs_mut().append_pair("lidEnabled", "true");
         }
+        if let Some(threshold) = config.stable_partial_result_threshold {
+            url.query_pairs_mut()
+                .append_pair("stableIntermediateThreshold", &threshold.to_string());
+        }
+        if let Some(endpoint_id) = config.custom_endpoint_id() {
+            url.query_pairs_mut().append_pair("cid", endpoint_id);
+        }
         if let Some(ref connection_id) = config.connection_id {
             url.query_pairs_mut()
                 .append_pair("X-ConnectionId", connection_id);
@@ -83,49 +102,134 @@ impl Client {
     pub async fn recognize_file(
         &self,
         path: impl Into<std::path::PathBuf>,
-    ) -> crate::Result<impl Stream<Item = crate::Result<Event>>> {
+    ) -> crate::Result<Recognition<impl Stream<Item = crate::Result<Event>>>> {
         let path = path.into();
         let file = tokio::fs::File::open(&path).await?;
-        let ext = path
-            .extension()
-            .ok_or_else(|| crate::Error::IOError("Missing file extension.".to_string()))?;
-
-        let (tx, rx) = tokio::sync::mpsc::channel(1024);
-
-        tokio::spawn(async move {
-            let mut reader = tokio::io::BufReader::new(file);
-            loop {
-                let mut chunk = vec![0; BUFFER_SIZE];
-                match reader.read(&mut chunk).await {
-                    Ok(0) => break,
-                    Ok(n) => {
-                        chunk.truncate(n);
-                        if let Err(e) = tx.send(chunk).await {
-                            warn!("Failed to send chunk: {}", e);
-                            break;
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to read chunk: {}", e);
-                        break;
-                    }
-                }
-            }
-        });
+        // The extension is only a hint: the format is detected from the content.
+        let hint = path.extension().and_then(|ext| ext.try_into().ok());
 
-        self.recognize(
-            ReceiverStream::new(rx),
-            ext.try_into()?,
-            AudioDevice::file(),
-        )
-        .await
+        let (audio, audio_format) = PullAudioInputStream::detect_format(file, hint).await?;
+        self.recognize(audio, audio_format, AudioDevice::file())
+            .await
+    }
+
+    /// Recognize the audio read from the reader.
+    ///
+    /// The audio format is detected from the first bytes.
+    pub async fn recognize_reader<R>(
+        &self,
+        reader: R,
+        audio_device: AudioDevice,
+    ) -> crate::Result<Recognition<impl Stream<Item = crate::Result<Event>>>>
+    where
+        R: AsyncRead + Sync + Send + Unpin + 'static,
+    {
+        let (audio, audio_format) = PullAudioInputStream::detect_format(reader, None).await?;
+        self.recognize(audio, audio_format, audio_device).await
     }
+
+    /// Recognize the audio stream continuously.
+    ///
+    /// The returned [`Recognition`] is the stream of events. It ends after the last
+    /// turn once the audio stream is completed, or when it is stopped or aborted.
+    ///
+    /// With [`ChannelMode::Split`], each channel of the audio is recognized on its own connection.
     pub async fn recognize<A>(
+        &self,
+        audio: A,
+        audio_format: AudioFormat,
+        audio_device: AudioDevice,
+    ) -> crate::Result<Recognition<impl Stream<Item = crate::Result<Event>>>>
+    where
+        A: Stream<Item = Vec<u8>> + Sync + Send + Unpin + 'static,
+    {
+        Ok(match self.config.channel_mode {
+            ChannelMode::Mixed => {
+                let (events, handle) = self
+                    .recognize_audio(audio, audio_format, audio_device)
+                    .await?;
+                Recognition::new(Either::Left(events), handle)
+            }
+            ChannelMode::Split => {
+                let (events, handle) = self
+                    .recognize_channels(audio, audio_format, audio_device)
+                    .await?;
+                Recognition::new(Either::Right(events), handle)
+            }
+        })
+    }
+
+    /// Recognize each channel of the PCM audio in its own session, and merge their events.
+    async fn recognize_channels<A>(
         &self,
         mut audio: A,
         audio_format: AudioFormat,
         audio_device: AudioDevice,
-    ) -> crate::Result<impl Stream<Item = crate::Result<Event>>>
+    ) -> crate::Result<(impl Stream<Item = crate::Result<Event>>, RecognitionHandle)>
+    where
+        A: Stream<Item = Vec<u8>> + Sync + Send + Unpin + 'static,
+    {
+        let (spec, buffered) = match audio_format {
+            AudioFormat::Wav => {
+                let (_, extra, spec) = extract_header_from_wav(&mut audio).await?;
+                (spec, extra)
+            }
+            AudioFormat::RawPcm {
+                sample_rate,
+                bits_per_sample,
+                channels,
+            } => {
+                let spec = WavSpec {
+                    sample_rate,
+                    bits_per_sample,
+                    channels,
+                    sample_format: SampleFormat::Int,
+                };
+                spec.validate()?;
+                (spec, vec![])
+            }
+            format => {
+                return Err(crate::Error::IOError(format!(
+                    "Invalid audio format to split the channels: {:?}",
+                    format
+                )))
+            }
+        };
+
+        let mut events = vec![];
+        let mut handles = vec![];
+        for (index, channel) in split_channels(audio, buffered, spec)
+            .into_iter()
+            .enumerate()
+        {
+            // The first channel is recognized on the connection of the client.
+            let client = match index {
+                0 => self.client.clone(),
+                _ => self.client.new_connection().await?,
+            };
+            let (channel_events, handle) = Client::new(client, self.config.clone())
+                .recognize_audio(channel, AudioFormat::Wav, audio_device.clone())
+                .await?;
+            events.push(channel_events);
+            handles.push(handle);
+        }
+
+        let handle = RecognitionHandle::merge(handles);
+        let aborted = handle.clone();
+        let events = futures_util::StreamExt::take_until(
+            MergedEvents::new(events),
+            Box::pin(async move { aborted.aborted().await }),
+        );
+        Ok((events, handle))
+    }
+
+    /// Recognize the audio in a single session.
+    async fn recognize_audio<A>(
+        &self,
+        audio: A,
+        audio_format: AudioFormat,
+        audio_device: AudioDevice,
+    ) -> crate::Result<(impl Stream<Item = crate::Result<Event>>, RecognitionHandle)>
     where
         A: Stream<Item = Vec<u8>> + Sync + Send + Unpin + 'static,
     {
@@ -133,136 +237,563 @@ impl Client {
         let session = Session::new();
         let config = self.config.clone();
         let client = self.client.clone();
-        let (restart_tx, mut restart_rx) = tokio::sync::mpsc::channel(1);
-
-        // Send the initial speech configuration.
-        client
-            .send(create_speech_config_message(
-                session.request_id().to_string(),
-                &config,
-                &audio_device,
-            ))
-            .await?;
-
-        // Send the initial context and audio header messages.
-        client
-            .send(create_speech_context_message(
-                session.request_id().to_string(),
-                &config,
-            ))
-            .await?;
+        let input = AudioInput::open(audio, audio_format, &config).await?;
+
+        // The speech configuration and context are sent at the start of each connection.
+        let spec = input.spec().copied();
+        let setup_config = config.clone();
+        let control_tx = stream_audio(client, session.clone(), input, move |request_id| {
+            Ok(vec![
+                create_speech_config_message(
+                    request_id.clone(),
+                    &setup_config,
+                    &audio_device,
+                    spec.as_ref(),
+                ),
+                create_speech_context_message(request_id, &setup_config)?,
+            ])
+        })
+        .await?;
+        let handle = RecognitionHandle::new(control_tx.clone());
 
+        // Build the output stream that filters and converts messages into events.
+        let session_filter = session.clone();
+        let session_convert = session.clone();
+        let convert_config = config.clone();
+        let control_resume = control_tx.clone();
+        let messages = messages.filter(move |msg| match msg {
+            Ok(m) => m.id == session_filter.request_id().to_string(),
+            Err(_) => true,
+        });
+        // A lost connection is resumed, with the audio sent again from the last final phrase.
+        let messages = Box::pin(futures_util::StreamExt::then(messages, move |msg| {
+            let control = control_resume.clone();
+            async move { resume_on_connection_lost(msg, &control).await }
+        }));
+        let output_stream = messages
+            .filter_map(move |msg| match msg? {
+                Ok(m) => convert_message_to_event(m, &session_convert, &convert_config),
+                Err(e) => Some(Err(e)),
+            })
+            .map(move |event| {
+                if let Ok(Event::SessionEnded(_)) = event {
+                    let _ = control_tx.try_send(AudioControl::Restart);
+                }
+                event
+            })
+            // The last turn ends after all the audio has been sent.
+            .stop_after(move |event| {
+                event.is_err()
+                    || matches!(event, Ok(Event::SessionEnded(_))) && session.is_audio_completed()
+            });
+
+        let aborted = handle.clone();
+        let output_stream = futures_util::StreamExt::take_until(
+            output_stream,
+            Box::pin(async move { aborted.aborted().await }),
+        );
+
+        Ok((output_stream, handle))
+    }
+
+    /// Recognize a single phrase from the audio stream.
+    ///
+    /// The recognition runs on the interactive endpoint, and the audio stops being sent as soon as
+    /// the first final phrase is received. The client must be connected with
+    /// [`RecognitionMode::Interactive`], otherwise a [`ConfigError`](crate::Error::ConfigError) is returned.
+    /// The channels are always recognized mixed, so [`ChannelMode::Split`] is rejected the same way.
+    ///
+    /// Errors happening after the recognition started are returned as [`RecognizeOnceResult::Cancelled`].
+    pub async fn recognize_once<A>(
+        &self,
+        audio: A,
+        audio_format: AudioFormat,
+        audio_device: AudioDevice,
+    ) -> crate::Result<RecognizeOnceResult>
+    where
+        A: Stream<Item = Vec<u8>> + Sync + Send + Unpin + 'static,
+    {
+        // The mode is part of the endpoint the client is connected to.
+        if !matches!(self.config.mode, RecognitionMode::Interactive) {
+            return Err(crate::Error::ConfigError(
+                "recognize_once requires a client connected with the interactive recognition mode."
+                    .to_string(),
+            ));
+        }
+
+        if matches!(self.config.channel_mode, ChannelMode::Split) {
+            return Err(crate::Error::ConfigError(
+                "recognize_once does not support the split channel mode.".to_string(),
+            ));
+        }
+
+        let messages = self.client.stream().await?;
+        let session = Session::new();
+        let config = self.config.clone();
+        let client = self.client.clone();
+        let input = AudioInput::open(audio, audio_format, &config).await?;
+
+        let spec = input.spec().copied();
+        let setup_config = config.clone();
+        let control_tx = stream_audio(client, session.clone(), input, move |request_id| {
+            Ok(vec![
+                create_speech_config_message(
+                    request_id.clone(),
+                    &setup_config,
+                    &audio_device,
+                    spec.as_ref(),
+                ),
+                create_speech_context_message(request_id, &setup_config)?,
+            ])
+        })
+        .await?;
+
+        let session_clone = session.clone();
+        let mut events = Box::pin(
+            messages
+                .filter(move |msg| match msg {
+                    Ok(m) => m.id == session.request_id().to_string(),
+                    Err(_) => true,
+                })
+                .filter_map(move |msg| match msg {
+                    Ok(m) => convert_message_to_event(m, &session_clone, &config),
+                    Err(e) => Some(Err(e)),
+                }),
+        );
+
+        let result = loop {
+            match events.next().await {
+                Some(Ok(Event::Recognized(event))) => {
+                    break RecognizeOnceResult::Recognized(event);
+                }
+                Some(Ok(Event::UnMatch(event))) => {
+                    break RecognizeOnceResult::NoMatch(event);
+                }
+                Some(Ok(Event::SessionEnded(_))) => {
+                    break RecognizeOnceResult::Cancelled(crate::Error::RuntimeError(
+                        "Session ended without a recognition result.".to_string(),
+                    ));
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => break RecognizeOnceResult::Cancelled(e),
+                None => {
+                    break RecognizeOnceResult::Cancelled(crate::Error::RuntimeError(
+                        "Stream ended without a recognition result.".to_string(),
+                    ))
+                }
+            }
+        };
+
+        let _ = control_tx.send(AudioControl::Stop).await;
+
+        Ok(result)
+    }
+}
+
+/// Signals sent to the task that streams the audio to the service.
+#[derive(Debug)]
+pub(crate) enum AudioControl {
+    /// A new turn started: send the audio header again.
+    Restart,
+    /// The connection was lost: connect again and send the unacknowledged audio.
+    ///
+    /// Replies whether the recognition was resumed.
+    Reconnect(tokio::sync::oneshot::Sender<crate::Result<bool>>),
+    /// Stop reading the audio and signal the end of audio to the service.
+    Stop,
+}
+
+/// The audio stream to send, with its header already read.
+pub(crate) struct AudioInput<A> {
+    audio: A,
+    format: AudioFormat,
+    header: Option<Vec<u8>>,
+    buffered: Vec<u8>,
+    spec: Option<WavSpec>,
+    processor: AudioProcessor,
+    pacer: Option<Pacer>,
+    replay: ReplayBuffer,
+}
+
+/// The optional processing of the PCM audio, before sending it.
+#[derive(Debug, Default)]
+struct AudioProcessor {
+    converter: Option<AudioConverter>,
+    voice_activity_detector: Option<VoiceActivityDetector>,
+}
+
+impl AudioProcessor {
+    fn process(&mut self, data: Vec<u8>, session: &Session) -> Vec<u8> {
+        let data = match self.converter.as_mut() {
+            Some(converter) => converter.process(&data),
+            None => data,
+        };
+        self.detect_voice(data, session)
+    }
+
+    fn flush(&mut self, session: &Session) -> Vec<u8> {
+        let data = match self.converter.as_mut() {
+            Some(converter) => converter.flush(),
+            None => vec![],
+        };
+        let mut data = self.detect_voice(data, session);
+        if let Some(detector) = self.voice_activity_detector.as_mut() {
+            let (tail, skipped) = detector.flush();
+            for (at, duration) in skipped {
+                session.on_audio_skipped(at, duration);
+            }
+            data.extend(tail);
+        }
+        data
+    }
+
+    fn detect_voice(&mut self, data: Vec<u8>, session: &Session) -> Vec<u8> {
+        match self.voice_activity_detector.as_mut() {
+            Some(detector) => {
+                let (data, skipped) = detector.process(&data);
+                for (at, duration) in skipped {
+                    session.on_audio_skipped(at, duration);
+                }
+                data
+            }
+            None => data,
+        }
+    }
+}
+
+impl<A> AudioInput<A>
+where
+    A: Stream<Item = Vec<u8>> + Sync + Send + Unpin + 'static,
+{
+    /// Read the header of the audio stream, when the format has one.
+    ///
+    /// The PCM audio is processed as set in the configuration.
+    pub(crate) async fn open(
+        mut audio: A,
+        format: AudioFormat,
+        config: &Config,
+    ) -> crate::Result<Self> {
         // For WAV audio, extract the header and extra data.
-        let (audio_header, extra) = match audio_format {
+        let (header, buffered, spec) = match format {
             AudioFormat::Wav => {
-                let (header, extra) = extract_header_from_wav(&mut audio).await?;
-                debug!(
-                    "Audio WAV header({}): {:?}",
-                    header.len(),
-                    header[..44].to_vec()
-                );
-                (Some(header), extra)
+                let (header, extra, spec) = extract_header_from_wav(&mut audio).await?;
+                debug!("Audio WAV header({}): {:?}", header.len(), spec);
+                (Some(header), extra, Some(spec))
             }
-            _ => (None, vec![]),
+            // For raw PCM audio, create the header from the declared spec.
+            AudioFormat::RawPcm {
+                sample_rate,
+                bits_per_sample,
+                channels,
+            } => {
+                let spec = WavSpec {
+                    sample_rate,
+                    bits_per_sample,
+                    channels,
+                    sample_format: SampleFormat::Int,
+                };
+                spec.validate()?;
+                (Some(spec.to_header()), vec![], Some(spec))
+            }
+            _ => (None, vec![], None),
         };
 
-        // Create the audio data buffer and seed it with any extra bytes.
-        let mut buffer = Vec::with_capacity(BUFFER_SIZE);
-        buffer.extend(extra);
-
-        client
-            .send(create_audio_header_message(
-                session.request_id().to_string(),
-                audio_format.clone(),
-                audio_header.as_deref(),
-            ))
-            .await?;
-
-        let _session = session.clone();
-        tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    // Handle any restart signal.
-                    _ = restart_rx.recv() => {
-                        tracing::info!("Refreshing audio header");
-                        _session.refresh();
-
-                        if client.send(create_audio_header_message(
-                            _session.request_id().to_string(),
-                            audio_format.clone(),
-                            audio_header.as_deref(),
-                        )).await.is_err() {
-                            warn!("Failed to refresh audio header");
-                            break;
+        let mut input = Self {
+            audio,
+            format,
+            header,
+            buffered,
+            spec,
+            processor: AudioProcessor::default(),
+            pacer: None,
+            // Without the spec, the audio acknowledged by the service is unknown: it is not replayed.
+            replay: ReplayBuffer::new(0, 1),
+        };
+
+        let converter = input
+            .spec
+            .filter(|_| config.audio_conversion)
+            .and_then(AudioConverter::new);
+        if let Some(converter) = converter {
+            debug!(
+                "Converting audio from {:?} to {:?}",
+                input.spec, TARGET_SPEC
+            );
+            input.header = Some(TARGET_SPEC.to_header());
+            input.spec = Some(TARGET_SPEC);
+            input.processor.converter = Some(converter);
+        }
+
+        if let Some(ref voice_activity_detection) = config.voice_activity_detection {
+            input.processor.voice_activity_detector = input.spec.and_then(|spec| {
+                VoiceActivityDetector::new(voice_activity_detection.clone(), spec)
+            });
+            if input.processor.voice_activity_detector.is_none() {
+                warn!("Voice activity detection is only available for PCM audio");
+            }
+        }
+
+        if let Some(spec) = input.spec {
+            input.replay =
+                ReplayBuffer::new(config.replay_buffer_size, spec.block_align() as usize);
+        }
+
+        if let Some(ref audio_pacing) = config.audio_pacing {
+            input.pacer = input.spec.and_then(|spec| Pacer::new(audio_pacing, spec));
+            if input.pacer.is_none() {
+                warn!("Audio pacing is only available for PCM audio, with a positive speed");
+            }
+        }
+
+        Ok(input)
+    }
+
+    /// The spec of the audio, when known.
+    pub(crate) fn spec(&self) -> Option<&WavSpec> {
+        self.spec.as_ref()
+    }
+}
+
+/// Send the setup messages and the audio header, and spawn the task that streams the audio to the service.
+///
+/// The setup messages of a turn, created for its request id, are sent again when the connection is resumed.
+/// Returns the channel used to control the streaming task.
+pub(crate) async fn stream_audio<A, F>(
+    client: BaseClient,
+    session: Session,
+    input: AudioInput<A>,
+    setup: F,
+) -> crate::Result<tokio::sync::mpsc::Sender<AudioControl>>
+where
+    A: Stream<Item = Vec<u8>> + Sync + Send + Unpin + 'static,
+    F: Fn(String) -> crate::Result<Vec<tokio_websockets::Message>> + Send + Sync + 'static,
+{
+    let (control_tx, mut control_rx) = tokio::sync::mpsc::channel(4);
+    let AudioInput {
+        mut audio,
+        format: audio_format,
+        header: audio_header,
+        buffered,
+        spec,
+        mut processor,
+        mut pacer,
+        mut replay,
+    } = input;
+
+    // Create the audio data buffer and seed it with any extra bytes.
+    let mut buffer = Vec::with_capacity(BUFFER_SIZE);
+    buffer.extend(processor.process(buffered, &session));
+
+    for message in setup(session.request_id().to_string())? {
+        client.send(message).await?;
+    }
+    client
+        .send(create_audio_header_message(
+            session.request_id().to_string(),
+            audio_format.clone(),
+            audio_header.as_deref(),
+        ))
+        .await?;
+
+    tokio::spawn(async move {
+        // Once the audio stream ended, the rest of the buffer is sent, then the end of audio.
+        let mut audio_ended = false;
+        // Once the audio is completed, the task only waits to resume the connection.
+        let mut completed = false;
+        // After a failed send, the audio is not read until the connection is resumed.
+        let mut disconnected = false;
+        loop {
+            // The next chunk is sent once it is full, or with the remaining bytes at the end of the audio.
+            let ready = !completed
+                && !disconnected
+                && (buffer.len() >= BUFFER_SIZE || audio_ended && !buffer.is_empty());
+            // The pacing delays the next chunk, while the control signals are still handled.
+            let send_at = match pacer.as_mut() {
+                Some(pacer) if ready => pacer.send_at(min(buffer.len(), BUFFER_SIZE)),
+                _ => None,
+            };
+            tokio::select! {
+                control = control_rx.recv() => {
+                    match control {
+                        // The last turn ended after the end of audio: there is no next turn.
+                        Some(AudioControl::Restart) if completed => {}
+                        // Handle any restart signal.
+                        Some(AudioControl::Restart) => {
+                            tracing::info!("Refreshing audio header");
+                            session.refresh(spec.as_ref());
+
+                            if client.send(create_audio_header_message(
+                                session.request_id().to_string(),
+                                audio_format.clone(),
+                                audio_header.as_deref(),
+                            )).await.is_err() {
+                                warn!("Failed to refresh audio header");
+                                break;
+                            }
                         }
-                    },
-                    // Process the next chunk from the audio stream.
-                    maybe_chunk = audio.next() => {
-                        match maybe_chunk {
-                            Some(chunk) => {
-                                // Append the new data to the buffer.
-                                buffer.extend(chunk);
-                                // While there is enough data, send it in fixed-size chunks.
-                                while buffer.len() >= BUFFER_SIZE {
-                                    let data: Vec<u8> = buffer.drain(..BUFFER_SIZE).collect();
-                                    if client.send(create_audio_message(_session.request_id().to_string(), Some(&data))).await.is_err() {
-                                        warn!("Failed to send audio message");
-                                        break;
-                                    }
-                                }
+                        Some(AudioControl::Reconnect(resumed)) => {
+                            // Only the audio after the last final phrase needs to be sent again.
+                            replay.acknowledge(acknowledged_bytes(spec.as_ref(), &session));
+                            // Without audio to send again, the recognition ends with the connection error.
+                            // The task ends too, and drops the audio: its producer is not blocked by it.
+                            if replay.is_disabled() || completed && replay.is_empty() {
+                                let _ = resumed.send(Ok(false));
+                                break;
                             }
-                            None => {
-                                // No more audio: flush remaining bytes in the buffer.
-                                while !buffer.is_empty() {
-                                    let data: Vec<u8> = buffer.drain(..min(buffer.len(), BUFFER_SIZE)).collect();
-                                    if client.send(create_audio_message(_session.request_id().to_string(), Some(&data))).await.is_err() {
-                                        warn!("Failed to send final audio chunk");
-                                        break;
-                                    }
+
+                            tracing::info!("Resuming the recognition on a new connection");
+                            let result = async {
+                                client.reconnect().await?;
+                                session.resume(match spec {
+                                    Some(spec) => spec.offset_of(replay.start()),
+                                    None => session.recognition_offset(),
+                                });
+                                let request_id = session.request_id().to_string();
+                                for message in setup(request_id.clone())? {
+                                    client.send(message).await?;
+                                }
+                                client.send(create_audio_header_message(
+                                    request_id.clone(),
+                                    audio_format.clone(),
+                                    audio_header.as_deref(),
+                                )).await?;
+                                for data in replay.chunks(BUFFER_SIZE) {
+                                    client.send(create_audio_message(request_id.clone(), Some(&data))).await?;
+                                }
+                                if completed {
+                                    client.send(create_audio_message(request_id, None)).await?;
+                                    session.set_audio_completed(true);
                                 }
-                                // Signal the end of audio.
-                                let _ = client.send(create_audio_message(_session.request_id().to_string(), None)).await;
-                                _session.set_audio_completed(true);
+                                Ok::<_, crate::Error>(true)
+                            }.await;
+                            let resumed_ok = matches!(result, Ok(true));
+                            let _ = resumed.send(result);
+                            // The recognition ends with the error of the new connection.
+                            if !resumed_ok {
                                 break;
                             }
+                            disconnected = false;
+                        }
+                        Some(AudioControl::Stop) if completed => {}
+                        // Stop reading the audio when requested.
+                        Some(AudioControl::Stop) => {
+                            debug!("Stopping audio stream");
+                            let _ = client.send(create_audio_message(session.request_id().to_string(), None)).await;
+                            session.set_audio_completed(true);
+                            completed = true;
+                        }
+                        // Nobody listens to the events anymore.
+                        None => {
+                            if !completed {
+                                let _ = client.send(create_audio_message(session.request_id().to_string(), None)).await;
+                                session.set_audio_completed(true);
+                            }
+                            break;
+                        }
+                    }
+                },
+                // Send the next chunk of audio, when it is due.
+                _ = tokio::time::sleep_until(send_at.unwrap_or_else(Instant::now)), if ready => {
+                    let data: Vec<u8> = buffer.drain(..min(buffer.len(), BUFFER_SIZE)).collect();
+                    if let Some(pacer) = pacer.as_mut() {
+                        pacer.on_sent(data.len());
+                    }
+                    // The chunk is kept before it is sent, to be sent again if the send fails.
+                    session.on_audio_sent(data.len());
+                    replay.acknowledge(acknowledged_bytes(spec.as_ref(), &session));
+                    replay.push(&data);
+                    if client.send(create_audio_message(session.request_id().to_string(), Some(&data))).await.is_err() {
+                        warn!("Failed to send audio message");
+                        disconnected = true;
+                    }
+                }
+                // Process the next chunk from the audio stream.
+                maybe_chunk = audio.next(), if !completed && !disconnected && !audio_ended && buffer.len() < BUFFER_SIZE => {
+                    match maybe_chunk {
+                        // Append the new data to the buffer.
+                        Some(chunk) => buffer.extend(processor.process(chunk, &session)),
+                        None => {
+                            buffer.extend(processor.flush(&session));
+                            audio_ended = true;
                         }
                     }
                 }
             }
-        });
 
-        // Build the output stream that filters and converts messages into events.
-        let session_clone = session.clone();
-        let output_stream = messages
-            .filter(move |msg| match msg {
-                Ok(m) => m.id == session.request_id().to_string(),
-                Err(_) => true,
-            })
-            .filter_map(move |msg| {
-                let session_ref = session_clone.clone();
-                match msg {
-                    Ok(m) => convert_message_to_event(m, &session_ref),
-                    Err(e) => Some(Err(e)),
-                }
-            })
-            .map(move |event| {
-                if let Ok(Event::SessionEnded(_)) = event {
-                    let _ = restart_tx.try_send(());
-                }
-                event
-            })
-            .stop_after(|event| event.is_err());
+            // Signal the end of audio, once all the audio is sent.
+            if audio_ended && buffer.is_empty() && !completed && !disconnected {
+                let _ = client
+                    .send(create_audio_message(session.request_id().to_string(), None))
+                    .await;
+                session.set_audio_completed(true);
+                completed = true;
+            }
+        }
+    });
+
+    Ok(control_tx)
+}
+
+/// The amount of audio data acknowledged by the service.
+///
+/// Without the spec, the duration of the audio sent is unknown: the audio sent is acknowledged when the
+/// final phrase is received.
+fn acknowledged_bytes(spec: Option<&WavSpec>, session: &Session) -> u64 {
+    match spec {
+        Some(spec) => spec.bytes_at(session.recognition_offset()),
+        None => session.acknowledged_bytes(),
+    }
+}
 
-        Ok(output_stream)
+/// Resume the recognition on a new connection, when the connection to the service is lost.
+///
+/// Returns the message to forward: the connection error, if the recognition can not be resumed.
+pub(crate) async fn resume_on_connection_lost(
+    message: crate::Result<Message>,
+    control: &tokio::sync::mpsc::Sender<AudioControl>,
+) -> Option<crate::Result<Message>> {
+    match message {
+        Err(crate::Error::ServerDisconnect(_) | crate::Error::ConnectionError(_)) => {
+            let (resumed_tx, resumed_rx) = tokio::sync::oneshot::channel();
+            if control
+                .send(AudioControl::Reconnect(resumed_tx))
+                .await
+                .is_err()
+            {
+                return Some(message);
+            }
+            match resumed_rx.await {
+                Ok(Ok(true)) => None,
+                Ok(Err(e)) => Some(Err(e)),
+                Ok(Ok(false)) | Err(_) => Some(message),
+            }
+        }
+        message => Some(message),
     }
 }
 
-fn convert_message_to_event(message: Message, session: &Session) -> Option<crate::Result<Event>> {
+/// Convert the service message to an event.
+///
+/// When only the language is identified, the phrases are converted to `LanguageDetected` events,
+/// and the hypotheses are ignored.
+fn convert_message_to_event(
+    message: Message,
+    session: &Session,
+    config: &Config,
+) -> Option<crate::Result<Event>> {
+    let identification_only = config.language_identification_only;
     match (message.path.as_str(), message.data, message.headers) {
-        ("turn.start", _, _) => Some(Ok(Event::SessionStarted(session.request_id()))),
+        ("turn.start", _, _) => Some(Ok(Event::SessionStarted(SessionEvent::new(
+            session.request_id(),
+        )))),
         ("speech.startdetected", Data::Text(Some(data)), _) => {
             serde_json::from_str::<crate::recognizer::message::SpeechStartDetected>(&data)
-                .map(|v| Event::StartDetected(session.request_id(), v.offset))
+                .map(|v| {
+                    Event::StartDetected(SpeechDetectedEvent::new(
+                        session.request_id(),
+                        session.audio_offset_at(v.offset),
+                    ))
+                })
                 .map(Ok)
                 .ok()
         }
@@ -270,15 +801,19 @@ fn convert_message_to_event(message: Message, session: &Session) -> Option<crate
             let value =
                 serde_json::from_str::<crate::recognizer::message::SpeechEndDetected>(&data)
                     .unwrap_or_default();
-            Some(Ok(Event::EndDetected(session.request_id(), value.offset)))
+            Some(Ok(Event::EndDetected(SpeechDetectedEvent::new(
+                session.request_id(),
+                session.audio_offset_at(value.offset),
+            ))))
         }
+        ("speech.hypothesis", _, _) | ("speech.fragment", _, _) if identification_only => None,
         ("speech.hypothesis", Data::Text(Some(data)), _)
         | ("speech.fragment", Data::Text(Some(data)), _) => {
             match serde_json::from_str::<crate::recognizer::message::SpeechHypothesis>(&data) {
                 Ok(value) => {
-                    let offset = value.offset + session.audio_offset();
+                    let offset = session.audio_offset_at(value.offset);
                     session.on_hypothesis_received(offset);
-                    Some(Ok(Event::Recognizing(
+                    Some(Ok(Event::Recognizing(RecognizedEvent::new(
                         session.request_id(),
                         Recognized {
                             text: value.text,
@@ -289,11 +824,12 @@ fn convert_message_to_event(message: Message, session: &Session) -> Option<crate
                                 )
                             }),
                             speaker_id: value.speaker_id,
+                            pronunciation_assessment: None,
                         },
                         offset,
                         value.duration,
                         data,
-                    )))
+                    ))))
                 }
                 Err(e) => Some(Err(crate::Error::ParseError(e.to_string()))),
             }
@@ -301,79 +837,111 @@ fn convert_message_to_event(message: Message, session: &Session) -> Option<crate
         ("speech.phrase", Data::Text(Some(data)), _) => {
             match serde_json::from_str::<crate::recognizer::message::SpeechPhrase>(&data) {
                 Ok(value) => {
-                    let offset = value.offset.unwrap_or(0) + session.audio_offset();
-                    let duration = value.duration.unwrap_or(0);
+                    let offset = session.audio_offset_at(value.offset.unwrap_or_default());
+                    let duration = value.duration.unwrap_or_default();
                     if value.recognition_status.is_end_of_dictation() {
                         return None;
                     }
-                    if value.recognition_status.is_no_match() {
-                        return Some(Ok(Event::UnMatch(
+                    session.on_phrase_recognized(value.offset.unwrap_or_default(), duration);
+                    if let Some(reason) = Option::<NoMatchReason>::from(&value.recognition_status) {
+                        return Some(Ok(Event::UnMatch(UnMatchEvent::new(
                             session.request_id(),
+                            reason,
                             offset,
                             duration,
                             data,
-                        )));
+                        ))));
                     }
-                    match serde_json::from_str::<crate::recognizer::message::SimpleSpeechPhrase>(
-                        &data,
-                    ) {
-                        Ok(simple) => Some(Ok(Event::Recognized(
-                            session.request_id(),
-                            Recognized {
-                                text: simple.display_text,
-                                primary_language: simple.primary_language.map(|l| {
-                                    PrimaryLanguage::new(
-                                        l.language.into(),
-                                        l.confidence.map_or(Confidence::Unknown, |c| c.into()),
-                                    )
-                                }),
-                                speaker_id: simple.speaker_id,
-                            },
-                            offset,
-                            duration,
-                            data,
-                        ))),
-                        Err(e) => Some(Err(crate::Error::ParseError(e.to_string()))),
+                    if let Some(err) = Option::<crate::Error>::from(&value.recognition_status) {
+                        return Some(Err(err));
+                    }
+                    if identification_only {
+                        return value.primary_language.map(|l| {
+                            Ok(Event::LanguageDetected(LanguageDetectedEvent::new(
+                                session.request_id(),
+                                PrimaryLanguage::new(
+                                    l.language.into(),
+                                    l.confidence.map_or(Confidence::Unknown, |c| c.into()),
+                                ),
+                                offset,
+                                duration,
+                                data,
+                            )))
+                        });
                     }
+                    let simple = match serde_json::from_str::<
+                        crate::recognizer::message::SimpleSpeechPhrase,
+                    >(&data)
+                    {
+                        Ok(simple) => simple,
+                        Err(e) => return Some(Err(crate::Error::ParseError(e.to_string()))),
+                    };
+                    let detailed = serde_json::from_str::<
+                        crate::recognizer::message::DetailedSpeechPhrase,
+                    >(&data)
+                    .ok();
+                    let alternatives = detailed.as_ref().map_or(&[][..], |d| &d.n_best[..]);
+                    let mut text = simple.display_text;
+                    let mut selected = alternatives.first();
+                    if let Some(filter) = config.result_filter.as_ref() {
+                        if !alternatives.is_empty() {
+                            let Some(phrase) = filter.select(alternatives, &config.grammar) else {
+                                return Some(Ok(Event::UnMatch(UnMatchEvent::new(
+                                    session.request_id(),
+                                    NoMatchReason::LowConfidence,
+                                    offset,
+                                    duration,
+                                    data,
+                                ))));
+                            };
+                            text = phrase.text().to_string();
+                            selected = Some(phrase);
+                        }
+                    }
+                    let pronunciation_assessment = selected
+                        .and_then(|phrase| PronunciationAssessment::from_phrase(phrase, session));
+
+                    Some(Ok(Event::Recognized(RecognizedEvent::new(
+                        session.request_id(),
+                        Recognized {
+                            text,
+                            primary_language: simple.primary_language.map(|l| {
+                                PrimaryLanguage::new(
+                                    l.language.into(),
+                                    l.confidence.map_or(Confidence::Unknown, |c| c.into()),
+                                )
+                            }),
+                            speaker_id: simple.speaker_id,
+                            pronunciation_assessment,
+                        },
+                        offset,
+                        duration,
+                        data,
+                    ))))
                 }
                 Err(e) => Some(Err(crate::Error::ParseError(e.to_string()))),
             }
         }
-        ("turn.end", _, _) => Some(Ok(Event::SessionEnded(session.request_id()))),
+        ("turn.end", _, _) => Some(Ok(Event::SessionEnded(SessionEvent::new(
+            session.request_id(),
+        )))),
         _ => None,
     }
 }
 
 async fn extract_header_from_wav(
     reader: &mut (impl Stream<Item = Vec<u8>> + Unpin + Send + Sync + 'static),
-) -> Result<(Vec<u8>, Vec<u8>), crate::Error> {
+) -> Result<(Vec<u8>, Vec<u8>, WavSpec), crate::Error> {
     let mut header = Vec::new();
 
     // Loop until the stream is exhausted.
     while let Some(chunk) = reader.next().await {
         header.extend(chunk);
 
-        // We need at least 12 bytes to check the RIFF and WAVE identifiers.
-        if header.len() < 12 {
-            continue;
-        }
-
-        // Check for a valid WAV header: bytes 0..4 must be "RIFF" and 8..12 must be "WAVE".
-        if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
-            return Err(crate::Error::ParseError("Invalid wav header".to_string()));
-        }
-
-        // Look for the "data" descriptor.
-        if let Some(pos) = header.windows(4).position(|w| w == b"data") {
-            // Ensure we have read the 4 bytes following "data" (i.e. the length field).
-            if header.len() < pos + 8 {
-                // Not enough bytes yet; continue reading.
-                continue;
-            }
-            // Split the header at the end of the "data" chunk descriptor and its length field.
-            let header_end = pos + 8;
-            let remainder = header.split_off(header_end);
-            return Ok((header, remainder));
+        // Split the header at the beginning of the data chunk, once it is complete.
+        if let Some((spec, header_len)) = parse_wav_header(&header)? {
+            let remainder = header.split_off(header_len);
+            return Ok((header, remainder, spec));
         }
     }
 
@@ -1,5 +1,8 @@
 use crate::config::Device;
-use crate::recognizer::Language;
+use crate::recognizer::{
+    AudioPacing, ChannelMode, Grammar, Language, PhraseList, PronunciationAssessmentConfig,
+    ResultFilter, VoiceActivityDetection,
+};
 use serde::{Deserialize, Serialize};
 
 /// The configuration for the recognizer.
@@ -17,15 +20,37 @@ pub struct Config {
 
     pub(crate) language_detect_mode: Option<LanguageDetectMode>,
 
-    pub(crate) phrases: Option<Vec<String>>,
+    pub(crate) language_id_priority: LanguageIdPriority,
+
+    pub(crate) language_identification_only: bool,
+
+    pub(crate) grammar: Grammar,
 
     pub(crate) custom_models: Option<Vec<(String, String)>>,
 
+    pub(crate) endpoint_id: Option<String>,
+
     pub(crate) connection_id: Option<String>, // todo: what is this for?
 
     pub(crate) store_audio: bool, // todo: is this needed?
 
     pub(crate) profanity: Profanity,
+
+    pub(crate) pronunciation_assessment: Option<PronunciationAssessmentConfig>,
+
+    pub(crate) result_filter: Option<ResultFilter>,
+
+    pub(crate) audio_conversion: bool,
+
+    pub(crate) voice_activity_detection: Option<VoiceActivityDetection>,
+
+    pub(crate) audio_pacing: Option<AudioPacing>,
+
+    pub(crate) replay_buffer_size: usize,
+
+    pub(crate) stable_partial_result_threshold: Option<u32>,
+
+    pub(crate) channel_mode: ChannelMode,
     // todo: check diarization https://learn.microsoft.com/en-us/azure/ai-services/speech-service/get-started-stt-diarization?tabs=macos&pivots=programming-language-javascript
     // probably will be moved from here and added to a separate module.
     //pub(crate) recognize_speaker: bool,
@@ -40,12 +65,23 @@ impl Default for Config {
             output_format: OutputFormat::Simple,
             mode: RecognitionMode::Conversation,
             language_detect_mode: None,
-            phrases: None,
+            language_id_priority: LanguageIdPriority::default(),
+            language_identification_only: false,
+            grammar: Grammar::default(),
             custom_models: None,
+            endpoint_id: None,
             connection_id: None,
             store_audio: false,
             device: Device::default(),
             profanity: Profanity::Masked,
+            pronunciation_assessment: None,
+            result_filter: None,
+            audio_conversion: false,
+            voice_activity_detection: None,
+            audio_pacing: None,
+            replay_buffer_size: 960_000,
+            stable_partial_result_threshold: None,
+            channel_mode: ChannelMode::default(),
         }
     }
 }
@@ -79,8 +115,10 @@ impl Config {
     /// Set the default language for the recognition.
     ///
     /// If needed multiple language detection, use the set_detect_languages method.
+    /// It disables the language identification only mode.
     pub fn set_language(mut self, language: Language) -> Self {
         self.languages = vec![language];
+        self.language_identification_only = false;
         self
     }
 
@@ -88,6 +126,7 @@ impl Config {
     ///
     /// The language detection is used to detect the language of the audio.
     /// This could not match the language of the audio, but it is used to provide better recognition.
+    /// It disables the language identification only mode.
     pub fn set_detect_languages(
         mut self,
         languages: Vec<Language>,
@@ -95,25 +134,142 @@ impl Config {
     ) -> Self {
         self.languages = languages;
         self.language_detect_mode = Some(language_detect_mode);
+        self.language_identification_only = false;
         self
     }
 
+    /// Only identify the spoken language among the given ones, without recognizing the speech.
+    ///
+    /// The detected languages are sent as [`Event::LanguageDetected`](crate::recognizer::Event::LanguageDetected) events:
+    /// once at the start of the audio, or for each phrase when the detection is continuous.
+    pub fn set_identify_languages(
+        mut self,
+        languages: Vec<Language>,
+        language_detect_mode: LanguageDetectMode,
+    ) -> Self {
+        self.languages = languages;
+        self.language_detect_mode = Some(language_detect_mode);
+        self.language_identification_only = true;
+        self
+    }
+
+    /// Set whether the language detection favors the accuracy or the latency.
+    ///
+    /// Default: [`LanguageIdPriority::Latency`].
+    pub fn set_language_id_priority(mut self, priority: LanguageIdPriority) -> Self {
+        self.language_id_priority = priority;
+        self
+    }
+
+    /// Whether the language of the audio is detected by the service.
+    pub(crate) fn is_language_detection_enabled(&self) -> bool {
+        self.languages.len() > 1 || self.language_identification_only
+    }
+
     /// Helping phrases to detect better the context.
     ///
-    /// Untested.
+    /// Shortcut for a [`Grammar`] with a single [`PhraseList`].
     pub fn set_phrases(mut self, phrases: Vec<String>) -> Self {
-        self.phrases = Some(phrases);
+        self.grammar = Grammar::new().with_phrase_list(PhraseList::new(phrases));
+        self
+    }
+
+    /// Set the phrase lists, class groups and reference grammars helping the recognition.
+    ///
+    /// The grammar is validated against the limits of the service when the recognition starts.
+    pub fn set_grammar(mut self, grammar: Grammar) -> Self {
+        self.grammar = grammar;
         self
     }
 
     /// Use custom Models.
     ///
-    /// Untested.
+    /// Each model is a pair of a language and the endpoint id of the model deployed for it.
+    /// Every language must be one of the configured languages. With a single language,
+    /// its model is used as the endpoint id of the recognition.
     pub fn set_custom_models(mut self, custom_models: Vec<(String, String)>) -> Self {
         self.custom_models = Some(custom_models);
         self
     }
 
+    /// Use a custom speech model, by the id of its deployed endpoint.
+    ///
+    /// Only for the single language recognition: with multiple languages,
+    /// set the model of each language with [`Config::set_custom_models`].
+    pub fn set_endpoint_id(mut self, endpoint_id: impl Into<String>) -> Self {
+        self.endpoint_id = Some(endpoint_id.into());
+        self
+    }
+
+    /// The endpoint id of the custom model of the single language recognition.
+    pub(crate) fn custom_endpoint_id(&self) -> Option<&str> {
+        if self.languages.len() > 1 {
+            return None;
+        }
+
+        self.endpoint_id.as_deref().or_else(|| {
+            let language = self.languages.first()?.to_string();
+            self.custom_models
+                .as_ref()?
+                .iter()
+                .find(|(l, _)| l.eq_ignore_ascii_case(&language))
+                .map(|(_, endpoint_id)| endpoint_id.as_str())
+        })
+    }
+
+    /// Check that the output format has the alternatives needed by the result filter.
+    pub(crate) fn validate_result_filter(&self) -> crate::Result<()> {
+        if self.result_filter.is_some() && self.output_format != OutputFormat::Detailed {
+            return Err(crate::Error::ConfigError(
+                "The result filter requires the detailed output format.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that the custom models match the configured languages.
+    pub(crate) fn validate_custom_models(&self) -> crate::Result<()> {
+        let invalid =
+            |reason: String| crate::Error::ConfigError(format!("Invalid custom model: {reason}."));
+
+        if let Some(endpoint_id) = self.endpoint_id.as_deref() {
+            if endpoint_id.trim().is_empty() {
+                return Err(invalid("the endpoint id is empty".to_string()));
+            }
+            if self.languages.len() > 1 {
+                return Err(invalid(
+                    "the endpoint id is only used for a single language".to_string(),
+                ));
+            }
+        }
+
+        let mut seen: Vec<&str> = vec![];
+        for (language, endpoint_id) in self.custom_models.iter().flatten() {
+            if !self
+                .languages
+                .iter()
+                .any(|l| l.to_string().eq_ignore_ascii_case(language))
+            {
+                return Err(invalid(format!(
+                    "the language {language} is not one of the configured languages"
+                )));
+            }
+            if seen.iter().any(|l| l.eq_ignore_ascii_case(language)) {
+                return Err(invalid(format!(
+                    "the language {language} has more than one model"
+                )));
+            }
+            if endpoint_id.trim().is_empty() {
+                return Err(invalid(format!(
+                    "the endpoint id of the language {language} is empty"
+                )));
+            }
+            seen.push(language);
+        }
+
+        Ok(())
+    }
+
     /// Set the recognition mode.
     ///
     /// *Only the Conversation mode was tested.*
@@ -126,11 +282,92 @@ impl Config {
     /// Set the output format of event responses.
     ///
     /// You will find the json in each event with Message.json() method.
+    /// The result filter requires the `Detailed` format: with the `Simple` format, the recognition
+    /// fails to start with a [`ConfigError`](crate::Error::ConfigError).
     pub fn set_output_format(mut self, format: OutputFormat) -> Self {
         self.output_format = format;
         self
     }
 
+    /// Enable the pronunciation assessment.
+    ///
+    /// The assessment requires the detailed output format, so the output format is set to `Detailed`.
+    /// The scores are available in the `pronunciation_assessment` field of the `Recognized` event.
+    pub fn set_pronunciation_assessment(
+        mut self,
+        pronunciation_assessment: PronunciationAssessmentConfig,
+    ) -> Self {
+        self.output_format = OutputFormat::Detailed;
+        self.pronunciation_assessment = Some(pronunciation_assessment);
+        self
+    }
+
+    /// Filter the recognized phrases by confidence, and choose the text among their alternatives.
+    ///
+    /// The alternatives require the detailed output format, so the output format is set to `Detailed`;
+    /// setting the `Simple` format afterwards makes the recognition fail to start.
+    pub fn set_result_filter(mut self, result_filter: ResultFilter) -> Self {
+        self.output_format = OutputFormat::Detailed;
+        self.result_filter = Some(result_filter);
+        self
+    }
+
+    /// Convert the PCM audio to 16 kHz mono 16-bit before sending it.
+    ///
+    /// This is the format preferred by the service: other sample rates and channel counts
+    /// get a worse accuracy and use more bandwidth. Only the `Wav` and `RawPcm` formats are converted.
+    pub fn enable_audio_conversion(mut self) -> Self {
+        self.audio_conversion = true;
+        self
+    }
+
+    /// Withhold the long silences of the PCM audio from the service.
+    ///
+    /// The reported offsets still match the timeline of the original audio.
+    pub fn set_voice_activity_detection(
+        mut self,
+        voice_activity_detection: VoiceActivityDetection,
+    ) -> Self {
+        self.voice_activity_detection = Some(voice_activity_detection);
+        self
+    }
+
+    /// Send the PCM audio at a multiple of real time, instead of as fast as it is read.
+    ///
+    /// Useful for files and buffered streams, that the service would otherwise throttle.
+    pub fn set_audio_pacing(mut self, audio_pacing: AudioPacing) -> Self {
+        self.audio_pacing = Some(audio_pacing);
+        self
+    }
+
+    /// Set the maximum amount of audio, in bytes, kept to be sent again if the connection drops.
+    ///
+    /// The audio sent after the last final phrase is replayed on a new connection, and the recognition
+    /// continues. Only the `Wav` and `RawPcm` formats are replayed: the duration of compressed audio is
+    /// unknown. Default: 960000 bytes, 30 seconds of 16kHz 16 bits mono audio.
+    /// Set 0 to end the recognition with the connection error instead.
+    pub fn set_replay_buffer_size(mut self, replay_buffer_size: usize) -> Self {
+        self.replay_buffer_size = replay_buffer_size;
+        self
+    }
+
+    /// Set the number of times a word must be hypothesized before it is returned in the `Recognizing` events.
+    ///
+    /// Higher values give more stable partial results, with more latency.
+    pub fn set_stable_partial_result_threshold(mut self, threshold: u32) -> Self {
+        self.stable_partial_result_threshold = Some(threshold);
+        self
+    }
+
+    /// Set how the channels of multi-channel PCM audio are recognized. Default: [`ChannelMode::Mixed`].
+    ///
+    /// With [`ChannelMode::Split`], each channel is recognized on its own connection, and the events
+    /// are tagged with their channel. It applies to the continuous recognition, not to `recognize_once`.
+    pub fn set_channel_mode(mut self, channel_mode: ChannelMode) -> Self {
+        self.channel_mode = channel_mode;
+        self
+    }
+
     //
     // pub fn enable_recognize_speaker(mut self) -> Self {
     //     self.recognize_speaker = true;
@@ -229,6 +466,18 @@ pub enum LanguageDetectMode {
     AtStart,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The priority of the language detection.
+pub enum LanguageIdPriority {
+    /// Detect the language as soon as possible.
+    #[serde(rename = "PrioritizeLatency")]
+    #[default]
+    Latency,
+    /// Listen to more audio, to detect the language more accurately.
+    #[serde(rename = "PrioritizeAccuracy")]
+    Accuracy,
+}
+
 #[derive(Debug, Clone, Default, Serialize)]
 /// Details of the source.
 ///
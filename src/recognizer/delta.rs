@@ -0,0 +1,389 @@
+use crate::recognizer::{Event, RecognizingDeltaEvent};
+use core::fmt;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::collections::HashMap;
+use tokio_stream::Stream;
+
+/// The change of the hypothesis of the current phrase, since the previous one.
+///
+/// The new hypothesis is the first `committed_prefix_len` bytes of the previous one, followed by
+/// the `appended` text. The `replaced` text is the end of the previous hypothesis, that was dropped.
+/// The prefix always ends on a word boundary: a rewritten word is replaced as a whole.
+///
+/// ```
+/// use azure_speech::recognizer::RecognizingDelta;
+///
+/// let mut text = "hello word".to_string();
+/// let delta = RecognizingDelta::between(&text, "hello world");
+/// assert_eq!(delta, RecognizingDelta::new(6, "world", "word"));
+///
+/// delta.apply(&mut text);
+/// assert_eq!(text, "hello world");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecognizingDelta {
+    /// The length, in bytes, of the previous hypothesis that is kept.
+    pub committed_prefix_len: usize,
+    /// The text added after the kept prefix.
+    pub appended: String,
+    /// The text of the previous hypothesis, after the kept prefix, that is removed.
+    pub replaced: String,
+}
+
+impl RecognizingDelta {
+    /// Create a delta.
+    pub fn new(
+        committed_prefix_len: usize,
+        appended: impl Into<String>,
+        replaced: impl Into<String>,
+    ) -> Self {
+        Self {
+            committed_prefix_len,
+            appended: appended.into(),
+            replaced: replaced.into(),
+        }
+    }
+
+    /// Compute the delta from the previous hypothesis to the next one.
+    pub fn between(previous: &str, next: &str) -> Self {
+        let common = previous
+            .char_indices()
+            .zip(next.chars())
+            .find(|((_, a), b)| a != b)
+            .map(|((i, _), _)| i)
+            .unwrap_or_else(|| previous.len().min(next.len()));
+
+        let committed_prefix_len = if is_boundary(previous, common) && is_boundary(next, common) {
+            common
+        } else {
+            // Go back to the start of the word.
+            previous[..common]
+                .char_indices()
+                .rev()
+                .find(|(_, c)| c.is_whitespace())
+                .map(|(i, c)| i + c.len_utf8())
+                .unwrap_or(0)
+        };
+
+        Self {
+            committed_prefix_len,
+            appended: next[committed_prefix_len..].to_string(),
+            replaced: previous[committed_prefix_len..].to_string(),
+        }
+    }
+
+    /// Apply the delta to the previous hypothesis.
+    pub fn apply(&self, text: &mut String) {
+        text.truncate(self.committed_prefix_len);
+        text.push_str(&self.appended);
+    }
+
+    /// Whether the hypothesis did not change.
+    pub fn is_empty(&self) -> bool {
+        self.appended.is_empty() && self.replaced.is_empty()
+    }
+}
+
+/// Whether the position is between two words of the text.
+fn is_boundary(text: &str, position: usize) -> bool {
+    position == 0
+        || position == text.len()
+        || text[position..].starts_with(char::is_whitespace)
+        || text[..position].ends_with(char::is_whitespace)
+}
+
+pin_project! {
+/// Stream for the [`recognizing_deltas`](crate::StreamExt::recognizing_deltas) method.
+#[must_use = "streams do nothing unless polled"]
+    pub struct RecognizingDeltas<St> {
+        #[pin]
+        stream: St,
+        // The hypothesis of the current phrase of each audio channel.
+        hypotheses: HashMap<Option<u16>, String>,
+    }
+}
+
+impl<St> RecognizingDeltas<St> {
+    pub(crate) fn new(stream: St) -> Self {
+        Self {
+            stream,
+            hypotheses: HashMap::new(),
+        }
+    }
+}
+
+impl<St> fmt::Debug for RecognizingDeltas<St>
+where
+    St: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecognizingDeltas")
+            .field("stream", &self.stream)
+            .field("hypotheses", &self.hypotheses)
+            .finish()
+    }
+}
+
+impl<St> Stream for RecognizingDeltas<St>
+where
+    St: Stream<Item = crate::Result<Event>>,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        this.stream.poll_next(cx).map(|ready| {
+            ready.map(|item| match item {
+                Ok(Event::Recognizing(event)) => {
+                    let hypothesis = this.hypotheses.entry(event.channel).or_default();
+                    let delta = RecognizingDelta::between(hypothesis, &event.result.text);
+                    *hypothesis = event.result.text;
+                    let mut delta = RecognizingDeltaEvent::new(
+                        event.request_id,
+                        delta,
+                        event.offset,
+                        event.duration,
+                        event.raw,
+                    );
+                    delta.channel = event.channel;
+                    Ok(Event::RecognizingDelta(delta))
+                }
+                Ok(event) => {
+                    // The next hypothesis starts a new phrase.
+                    if matches!(
+                        event,
+                        Event::Recognized(..)
+                            | Event::UnMatch(..)
+                            | Event::SessionStarted(..)
+                            | Event::SessionEnded(..)
+                    ) {
+                        this.hypotheses.remove(&event.channel());
+                    }
+                    Ok(event)
+                }
+                Err(e) => Err(e),
+            })
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::{
+        NoMatchReason, Recognized, RecognizedEvent, SessionEvent, UnMatchEvent,
+    };
+    use crate::StreamExt as _;
+    use crate::Ticks;
+    use tokio_stream::StreamExt;
+    use uuid::Uuid;
+
+    fn delta(previous: &str, next: &str) -> RecognizingDelta {
+        let delta = RecognizingDelta::between(previous, next);
+
+        // Applying the delta always gives the next hypothesis back.
+        let mut text = previous.to_string();
+        delta.apply(&mut text);
+        assert_eq!(text, next);
+        assert_eq!(
+            format!(
+                "{}{}",
+                &previous[..delta.committed_prefix_len],
+                delta.replaced
+            ),
+            previous
+        );
+
+        delta
+    }
+
+    #[test]
+    fn test_first_hypothesis() {
+        assert_eq!(delta("", "hello"), RecognizingDelta::new(0, "hello", ""));
+    }
+
+    #[test]
+    fn test_appended_words() {
+        assert_eq!(
+            delta("hello", "hello world"),
+            RecognizingDelta::new(5, " world", "")
+        );
+        assert_eq!(
+            delta("hello ", "hello world"),
+            RecognizingDelta::new(6, "world", "")
+        );
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let delta = delta("hello world", "hello world");
+        assert_eq!(delta, RecognizingDelta::new(11, "", ""));
+        assert!(delta.is_empty());
+    }
+
+    #[test]
+    fn test_shrinking_hypothesis() {
+        assert_eq!(
+            delta("hello world how", "hello world"),
+            RecognizingDelta::new(11, "", " how")
+        );
+        assert_eq!(delta("hello", ""), RecognizingDelta::new(0, "", "hello"));
+    }
+
+    #[test]
+    fn test_rewritten_last_word() {
+        assert_eq!(
+            delta("hello word", "hello world"),
+            RecognizingDelta::new(6, "world", "word")
+        );
+        assert_eq!(
+            delta("hello world", "hello word"),
+            RecognizingDelta::new(6, "word", "world")
+        );
+    }
+
+    #[test]
+    fn test_extended_word_is_replaced() {
+        assert_eq!(
+            delta("hel", "hello"),
+            RecognizingDelta::new(0, "hello", "hel")
+        );
+        assert_eq!(
+            delta("the cat", "the cats sat"),
+            RecognizingDelta::new(4, "cats sat", "cat")
+        );
+    }
+
+    #[test]
+    fn test_rewritten_earlier_words() {
+        assert_eq!(
+            delta("the cat sat on the mat", "the hat sat on the mat today"),
+            RecognizingDelta::new(4, "hat sat on the mat today", "cat sat on the mat")
+        );
+        assert_eq!(
+            delta("recognize speech", "wreck a nice beach"),
+            RecognizingDelta::new(0, "wreck a nice beach", "recognize speech")
+        );
+    }
+
+    #[test]
+    fn test_multibyte_characters() {
+        assert_eq!(
+            delta("café au lait", "café olé"),
+            RecognizingDelta::new(6, "olé", "au lait")
+        );
+        assert_eq!(
+            delta("你好 世界", "你好 世人"),
+            RecognizingDelta::new(7, "世人", "世界")
+        );
+        assert_eq!(
+            delta("你好", "你们"),
+            RecognizingDelta::new(0, "你们", "你好")
+        );
+    }
+
+    fn recognizing(text: &str) -> crate::Result<Event> {
+        Ok(Event::Recognizing(RecognizedEvent::new(
+            Uuid::nil(),
+            recognized(text),
+            Ticks::ZERO,
+            Ticks::ZERO,
+            String::new(),
+        )))
+    }
+
+    fn recognized(text: &str) -> Recognized {
+        Recognized {
+            text: text.to_string(),
+            primary_language: None,
+            speaker_id: None,
+            pronunciation_assessment: None,
+        }
+    }
+
+    async fn deltas(events: Vec<crate::Result<Event>>) -> Vec<Option<RecognizingDelta>> {
+        tokio_stream::iter(events)
+            .recognizing_deltas()
+            .map(|event| match event {
+                Ok(Event::RecognizingDelta(event)) => Some(event.delta().clone()),
+                _ => None,
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_stream_resets_on_each_phrase() {
+        let deltas = deltas(vec![
+            Ok(Event::SessionStarted(SessionEvent::new(Uuid::nil()))),
+            recognizing("hello"),
+            recognizing("hello world"),
+            Ok(Event::Recognized(RecognizedEvent::new(
+                Uuid::nil(),
+                recognized("Hello world."),
+                Ticks::ZERO,
+                Ticks::ZERO,
+                String::new(),
+            ))),
+            recognizing("how"),
+            Ok(Event::UnMatch(UnMatchEvent::new(
+                Uuid::nil(),
+                NoMatchReason::NotRecognized,
+                Ticks::ZERO,
+                Ticks::ZERO,
+                String::new(),
+            ))),
+            recognizing("are"),
+            Err(crate::Error::RuntimeError("failed".to_string())),
+        ])
+        .await;
+
+        assert_eq!(
+            deltas,
+            vec![
+                None,
+                Some(RecognizingDelta::new(0, "hello", "")),
+                Some(RecognizingDelta::new(5, " world", "")),
+                None,
+                Some(RecognizingDelta::new(0, "how", "")),
+                None,
+                Some(RecognizingDelta::new(0, "are", "")),
+                None,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_keeps_a_hypothesis_per_channel() {
+        let deltas: Vec<_> = tokio_stream::iter(vec![
+            recognizing("hello").map(|event| event.with_channel(0)),
+            recognizing("good").map(|event| event.with_channel(1)),
+            recognizing("hello world").map(|event| event.with_channel(0)),
+            recognizing("good morning").map(|event| event.with_channel(1)),
+        ])
+        .recognizing_deltas()
+        .map(|event| match event {
+            Ok(Event::RecognizingDelta(event)) => (event.channel(), event.delta().clone()),
+            _ => panic!("expected a delta"),
+        })
+        .collect()
+        .await;
+
+        assert_eq!(
+            deltas,
+            vec![
+                (Some(0), RecognizingDelta::new(0, "hello", "")),
+                (Some(1), RecognizingDelta::new(0, "good", "")),
+                (Some(0), RecognizingDelta::new(5, " world", "")),
+                (Some(1), RecognizingDelta::new(4, " morning", "")),
+            ]
+        );
+    }
+}
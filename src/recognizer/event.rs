@@ -1,4 +1,4 @@
-use crate::recognizer::Language;
+use crate::recognizer::{Language, PronunciationAssessment, RecognizingDelta};
 use crate::RequestId;
 
 /// The raw text of message.
@@ -9,45 +9,449 @@ pub type RawMessage = String;
 /// Recognizer events.
 ///
 /// The events are used to notify the user of the progress of the speech recognition.
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Each event wraps a struct, whose fields are read with its accessors.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum Event {
     /// The session started.
-    SessionStarted(RequestId),
+    SessionStarted(SessionEvent),
 
     /// The session ended.
-    SessionEnded(RequestId),
+    SessionEnded(SessionEvent),
 
     /// The speech recognition started.
-    StartDetected(RequestId, Offset),
+    StartDetected(SpeechDetectedEvent),
     /// The speech recognition ended.
-    EndDetected(RequestId, Offset),
+    EndDetected(SpeechDetectedEvent),
 
     /// Recognizing event.
-    Recognizing(RequestId, Recognized, Offset, Duration, RawMessage),
+    Recognizing(RecognizedEvent),
+
+    /// Only the change of the hypothesis, since the previous `Recognizing` event of the phrase.
+    ///
+    /// Sent instead of `Recognizing`, by the [`recognizing_deltas`](crate::StreamExt::recognizing_deltas) stream.
+    RecognizingDelta(RecognizingDeltaEvent),
 
     /// Recognized event.
-    Recognized(RequestId, Recognized, Offset, Duration, RawMessage),
+    Recognized(RecognizedEvent),
+
+    /// The language of a phrase was detected.
+    ///
+    /// Only sent when the recognizer identifies the languages without recognizing the speech.
+    LanguageDetected(LanguageDetectedEvent),
 
     /// UnMatch event.
     /// This event is triggered when the speech recognition does not match any text.
-    UnMatch(RequestId, Offset, Duration, RawMessage),
+    UnMatch(UnMatchEvent),
     //Cancelled(RequestId, Offset, crate::Error),
 }
 
+impl Event {
+    /// The index of the audio channel, when the channels are recognized separately.
+    ///
+    /// See [`ChannelMode::Split`](crate::recognizer::ChannelMode::Split).
+    pub fn channel(&self) -> Option<u16> {
+        match self {
+            Event::SessionStarted(event) | Event::SessionEnded(event) => event.channel,
+            Event::StartDetected(event) | Event::EndDetected(event) => event.channel,
+            Event::Recognizing(event) | Event::Recognized(event) => event.channel,
+            Event::RecognizingDelta(event) => event.channel,
+            Event::LanguageDetected(event) => event.channel,
+            Event::UnMatch(event) => event.channel,
+        }
+    }
+
+    /// The offset of the event, from the start of the audio, when it has one.
+    pub(crate) fn offset(&self) -> Option<Offset> {
+        match self {
+            Event::SessionStarted(_) | Event::SessionEnded(_) => None,
+            Event::StartDetected(event) | Event::EndDetected(event) => Some(event.offset),
+            Event::Recognizing(event) | Event::Recognized(event) => Some(event.offset),
+            Event::RecognizingDelta(event) => Some(event.offset),
+            Event::LanguageDetected(event) => Some(event.offset),
+            Event::UnMatch(event) => Some(event.offset),
+        }
+    }
+
+    /// Tag the event with the audio channel.
+    pub(crate) fn with_channel(mut self, channel: u16) -> Self {
+        let tag = match &mut self {
+            Event::SessionStarted(event) | Event::SessionEnded(event) => &mut event.channel,
+            Event::StartDetected(event) | Event::EndDetected(event) => &mut event.channel,
+            Event::Recognizing(event) | Event::Recognized(event) => &mut event.channel,
+            Event::RecognizingDelta(event) => &mut event.channel,
+            Event::LanguageDetected(event) => &mut event.channel,
+            Event::UnMatch(event) => &mut event.channel,
+        };
+        *tag = Some(channel);
+        self
+    }
+}
+
+/// The start or the end of a session.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct SessionEvent {
+    pub(crate) request_id: RequestId,
+    pub(crate) channel: Option<u16>,
+}
+
+impl SessionEvent {
+    pub(crate) fn new(request_id: RequestId) -> Self {
+        Self {
+            request_id,
+            channel: None,
+        }
+    }
+
+    /// The id of the request.
+    pub fn request_id(&self) -> RequestId {
+        self.request_id
+    }
+
+    /// The index of the audio channel, when the channels are recognized separately.
+    pub fn channel(&self) -> Option<u16> {
+        self.channel
+    }
+}
+
+/// The start or the end of the speech was detected.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct SpeechDetectedEvent {
+    pub(crate) request_id: RequestId,
+    pub(crate) offset: Offset,
+    pub(crate) channel: Option<u16>,
+}
+
+impl SpeechDetectedEvent {
+    pub(crate) fn new(request_id: RequestId, offset: Offset) -> Self {
+        Self {
+            request_id,
+            offset,
+            channel: None,
+        }
+    }
+
+    /// The id of the request.
+    pub fn request_id(&self) -> RequestId {
+        self.request_id
+    }
+
+    /// The index of the audio channel, when the channels are recognized separately.
+    pub fn channel(&self) -> Option<u16> {
+        self.channel
+    }
+
+    /// The offset of the detection, from the start of the audio.
+    pub fn offset(&self) -> Offset {
+        self.offset
+    }
+}
+
+/// A phrase recognized, or the current hypothesis of the phrase being recognized.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct RecognizedEvent {
+    pub(crate) request_id: RequestId,
+    pub(crate) result: Recognized,
+    pub(crate) offset: Offset,
+    pub(crate) duration: Duration,
+    pub(crate) raw: RawMessage,
+    pub(crate) channel: Option<u16>,
+}
+
+impl RecognizedEvent {
+    pub(crate) fn new(
+        request_id: RequestId,
+        result: Recognized,
+        offset: Offset,
+        duration: Duration,
+        raw: RawMessage,
+    ) -> Self {
+        Self {
+            request_id,
+            result,
+            offset,
+            duration,
+            raw,
+            channel: None,
+        }
+    }
+
+    /// The id of the request.
+    pub fn request_id(&self) -> RequestId {
+        self.request_id
+    }
+
+    /// The index of the audio channel, when the channels are recognized separately.
+    pub fn channel(&self) -> Option<u16> {
+        self.channel
+    }
+
+    /// The recognized text.
+    pub fn result(&self) -> &Recognized {
+        &self.result
+    }
+
+    /// The offset of the phrase, from the start of the audio.
+    pub fn offset(&self) -> Offset {
+        self.offset
+    }
+
+    /// The duration of the phrase.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The message received from the service.
+    pub fn raw(&self) -> &RawMessage {
+        &self.raw
+    }
+
+    /// Take the recognized text.
+    pub fn into_result(self) -> Recognized {
+        self.result
+    }
+}
+
+/// The change of the hypothesis of the phrase being recognized.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct RecognizingDeltaEvent {
+    pub(crate) request_id: RequestId,
+    pub(crate) delta: RecognizingDelta,
+    pub(crate) offset: Offset,
+    pub(crate) duration: Duration,
+    pub(crate) raw: RawMessage,
+    pub(crate) channel: Option<u16>,
+}
+
+impl RecognizingDeltaEvent {
+    pub(crate) fn new(
+        request_id: RequestId,
+        delta: RecognizingDelta,
+        offset: Offset,
+        duration: Duration,
+        raw: RawMessage,
+    ) -> Self {
+        Self {
+            request_id,
+            delta,
+            offset,
+            duration,
+            raw,
+            channel: None,
+        }
+    }
+
+    /// The id of the request.
+    pub fn request_id(&self) -> RequestId {
+        self.request_id
+    }
+
+    /// The index of the audio channel, when the channels are recognized separately.
+    pub fn channel(&self) -> Option<u16> {
+        self.channel
+    }
+
+    /// The change since the previous hypothesis.
+    pub fn delta(&self) -> &RecognizingDelta {
+        &self.delta
+    }
+
+    /// The offset of the phrase, from the start of the audio.
+    pub fn offset(&self) -> Offset {
+        self.offset
+    }
+
+    /// The duration of the phrase, so far.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The message received from the service.
+    pub fn raw(&self) -> &RawMessage {
+        &self.raw
+    }
+}
+
+/// The language detected for a phrase.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct LanguageDetectedEvent {
+    pub(crate) request_id: RequestId,
+    pub(crate) language: PrimaryLanguage,
+    pub(crate) offset: Offset,
+    pub(crate) duration: Duration,
+    pub(crate) raw: RawMessage,
+    pub(crate) channel: Option<u16>,
+}
+
+impl LanguageDetectedEvent {
+    pub(crate) fn new(
+        request_id: RequestId,
+        language: PrimaryLanguage,
+        offset: Offset,
+        duration: Duration,
+        raw: RawMessage,
+    ) -> Self {
+        Self {
+            request_id,
+            language,
+            offset,
+            duration,
+            raw,
+            channel: None,
+        }
+    }
+
+    /// The id of the request.
+    pub fn request_id(&self) -> RequestId {
+        self.request_id
+    }
+
+    /// The index of the audio channel, when the channels are recognized separately.
+    pub fn channel(&self) -> Option<u16> {
+        self.channel
+    }
+
+    /// The detected language, and the confidence of the detection.
+    pub fn language(&self) -> &PrimaryLanguage {
+        &self.language
+    }
+
+    /// The offset of the phrase, from the start of the audio.
+    pub fn offset(&self) -> Offset {
+        self.offset
+    }
+
+    /// The duration of the phrase.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The message received from the service.
+    pub fn raw(&self) -> &RawMessage {
+        &self.raw
+    }
+}
+
+/// A phrase that did not match any text.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct UnMatchEvent {
+    pub(crate) request_id: RequestId,
+    pub(crate) reason: NoMatchReason,
+    pub(crate) offset: Offset,
+    pub(crate) duration: Duration,
+    pub(crate) raw: RawMessage,
+    pub(crate) channel: Option<u16>,
+}
+
+impl UnMatchEvent {
+    pub(crate) fn new(
+        request_id: RequestId,
+        reason: NoMatchReason,
+        offset: Offset,
+        duration: Duration,
+        raw: RawMessage,
+    ) -> Self {
+        Self {
+            request_id,
+            reason,
+            offset,
+            duration,
+            raw,
+            channel: None,
+        }
+    }
+
+    /// The id of the request.
+    pub fn request_id(&self) -> RequestId {
+        self.request_id
+    }
+
+    /// The index of the audio channel, when the channels are recognized separately.
+    pub fn channel(&self) -> Option<u16> {
+        self.channel
+    }
+
+    /// Why the phrase did not match any text.
+    pub fn reason(&self) -> NoMatchReason {
+        self.reason
+    }
+
+    /// The offset of the phrase, from the start of the audio.
+    pub fn offset(&self) -> Offset {
+        self.offset
+    }
+
+    /// The duration of the phrase.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The message received from the service.
+    pub fn raw(&self) -> &RawMessage {
+        &self.raw
+    }
+}
+
+/// The result of a single-shot recognition.
+///
+/// Returned by [`Client::recognize_once`](crate::recognizer::Client::recognize_once).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum RecognizeOnceResult {
+    /// The speech was recognized.
+    Recognized(RecognizedEvent),
+    /// The speech was not recognized.
+    NoMatch(UnMatchEvent),
+    /// The recognition was cancelled before a result was received.
+    Cancelled(crate::Error),
+}
+
+/// The reason why the speech was not recognized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum NoMatchReason {
+    /// Speech was detected, but not recognized.
+    NotRecognized,
+    /// The start of the audio stream contained only silence, and the service timed out waiting for speech.
+    InitialSilenceTimeout,
+    /// The start of the audio stream contained only noise, and the service timed out waiting for speech.
+    InitialBabbleTimeout,
+    /// The keyword that starts the recognition was not recognized.
+    KeywordNotRecognized,
+    /// The speech was recognized, below the minimum confidence of the [`ResultFilter`](crate::recognizer::ResultFilter).
+    LowConfidence,
+}
+
 /// The offset of the speech recognition.
 ///
-/// The offset is the time in milliseconds from the start of the conversation.
-pub type Offset = u64;
+/// The offset is the time, in ticks of 100 nanoseconds, from the start of the audio.
+pub type Offset = crate::Ticks;
 
 /// The duration of the speech recognition.
 ///
-/// The duration is the time in milliseconds of the speech recognition.
-pub type Duration = u64;
+/// The duration is the time, in ticks of 100 nanoseconds, of the recognized speech.
+pub type Duration = crate::Ticks;
 
 /// The recognized text.
 ///
 /// Contains the recognized text, the primary language and the speaker id.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Recognized {
     /// The recognized text.
     pub text: String,
@@ -58,12 +462,21 @@ pub struct Recognized {
     /// The speaker id of the recognized text.
     /// This will be None if the detection of the speaker is not activated.
     pub speaker_id: Option<String>,
+
+    /// The pronunciation assessment of the recognized text.
+    /// This will be None if the pronunciation assessment is not configured.
+    pub pronunciation_assessment: Option<PronunciationAssessment>,
 }
 
 /// The confidence of the speech recognition.
 ///
 /// The confidence is the confidence of the speech recognition.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Confidence {
     Low,
     Normal,
@@ -91,6 +504,7 @@ impl From<String> for Confidence {
 
 /// Primary language
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrimaryLanguage {
     /// The language code
     pub language: Language,
@@ -106,3 +520,104 @@ impl PrimaryLanguage {
         }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::recognizer::{ErrorType, Language, WordAssessment};
+    use crate::Ticks;
+    use uuid::Uuid;
+
+    fn round_trip<T>(value: &T) -> T
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_events_round_trip() {
+        let recognized = Recognized {
+            text: "Ciao mondo.".to_string(),
+            primary_language: Some(PrimaryLanguage::new(Language::ItIt, Confidence::High)),
+            speaker_id: Some("Guest-1".to_string()),
+            pronunciation_assessment: Some(PronunciationAssessment {
+                accuracy_score: 90.0,
+                words: vec![WordAssessment {
+                    word: "ciao".to_string(),
+                    offset: Ticks::new(100),
+                    duration: Ticks::new(200),
+                    accuracy_score: Some(80.0),
+                    error_type: ErrorType::Mispronunciation,
+                    phonemes: vec![],
+                }],
+                ..Default::default()
+            }),
+        };
+        let events = vec![
+            Event::SessionStarted(SessionEvent::new(Uuid::new_v4())),
+            Event::StartDetected(SpeechDetectedEvent::new(Uuid::nil(), Ticks::new(100))),
+            Event::Recognized(RecognizedEvent::new(
+                Uuid::nil(),
+                recognized,
+                Ticks::new(100),
+                Ticks::new(500),
+                "{}".to_string(),
+            )),
+            Event::RecognizingDelta(RecognizingDeltaEvent::new(
+                Uuid::nil(),
+                RecognizingDelta::new(5, " mondo", ""),
+                Ticks::ZERO,
+                Ticks::new(500),
+                String::new(),
+            )),
+            Event::LanguageDetected(LanguageDetectedEvent::new(
+                Uuid::nil(),
+                PrimaryLanguage::new(Language::Custom("xx-XX".to_string()), Confidence::Unknown),
+                Ticks::ZERO,
+                Ticks::new(500),
+                String::new(),
+            )),
+            Event::UnMatch(UnMatchEvent::new(
+                Uuid::nil(),
+                NoMatchReason::KeywordNotRecognized,
+                Ticks::ZERO,
+                Ticks::new(500),
+                String::new(),
+            )),
+        ];
+
+        for event in events {
+            assert_eq!(round_trip(&event), event);
+        }
+    }
+
+    #[test]
+    fn test_language_is_serialized_as_code() {
+        let language = PrimaryLanguage::new(Language::ItIt, Confidence::High);
+
+        assert_eq!(
+            serde_json::to_value(&language).unwrap(),
+            serde_json::json!({"language": "it-IT", "confidence": "high"})
+        );
+    }
+
+    #[test]
+    fn test_recognize_once_result_round_trip() {
+        let results = vec![
+            RecognizeOnceResult::NoMatch(UnMatchEvent::new(
+                Uuid::nil(),
+                NoMatchReason::InitialSilenceTimeout,
+                Ticks::ZERO,
+                Ticks::new(50_000_000),
+                String::new(),
+            )),
+            RecognizeOnceResult::Cancelled(crate::Error::ServerDisconnect("closed".to_string())),
+            RecognizeOnceResult::Cancelled(crate::Error::Timeout),
+        ];
+
+        for result in results {
+            assert_eq!(round_trip(&result), result);
+        }
+    }
+}
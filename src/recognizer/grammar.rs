@@ -0,0 +1,429 @@
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+/// The maximum number of phrases, across all the phrase lists and class groups.
+pub const MAX_PHRASES: usize = 500;
+
+/// The maximum length of a phrase, in characters.
+pub const MAX_PHRASE_LENGTH: usize = 100;
+
+/// The range of the phrase weights. `1.0` is the default weight of the service.
+pub const PHRASE_WEIGHT_RANGE: std::ops::RangeInclusive<f64> = 0.0..=2.0;
+
+/// The grammars helping the service to recognize the domain words.
+///
+/// Sent in the `dgi` (dynamic grammar) section of the `speech.context` message.
+///
+/// # Example
+///
+/// ```
+/// use azure_speech::recognizer::{ClassEntry, ClassGroup, Config, Grammar, PhraseList};
+///
+/// let config = Config::default().set_grammar(
+///     Grammar::new()
+///         .with_phrase_list(PhraseList::new(vec!["Contoso", "Jessie"]).with_weight(1.5))
+///         .with_class_group(ClassGroup::people(vec![
+///             ClassEntry::new("Jessie Doe").with_synonyms(vec!["JD"]),
+///         ]))
+///         .with_reference_grammar("luis/00000000-0000-0000-0000-000000000000-PRODUCTION"),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Grammar {
+    pub(crate) phrase_lists: Vec<PhraseList>,
+    pub(crate) class_groups: Vec<ClassGroup>,
+    pub(crate) reference_grammars: Vec<String>,
+}
+
+impl Grammar {
+    /// Create an empty grammar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a list of phrases.
+    pub fn with_phrase_list(mut self, phrase_list: PhraseList) -> Self {
+        self.phrase_lists.push(phrase_list);
+        self
+    }
+
+    /// Add a group of class entries.
+    pub fn with_class_group(mut self, class_group: ClassGroup) -> Self {
+        self.class_groups.push(class_group);
+        self
+    }
+
+    /// Reference a grammar stored in the service, by id.
+    pub fn with_reference_grammar(mut self, id: impl Into<String>) -> Self {
+        self.reference_grammars.push(id.into());
+        self
+    }
+
+    /// Whether the grammar has nothing to send.
+    pub fn is_empty(&self) -> bool {
+        self.phrase_lists.is_empty()
+            && self.class_groups.is_empty()
+            && self.reference_grammars.is_empty()
+    }
+
+    /// Check the grammar against the limits of the service.
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        let mut names = HashSet::new();
+        let groups = self
+            .phrase_lists
+            .iter()
+            .map(|list| list.name.as_deref())
+            .chain(self.class_groups.iter().map(|group| group.name.as_deref()));
+        for name in groups.flatten() {
+            if name.trim().is_empty() {
+                return Err(invalid("a group name is empty"));
+            }
+            if !names.insert(name) {
+                return Err(invalid(&format!("the group name '{name}' is duplicated")));
+            }
+        }
+
+        let phrases = self
+            .phrase_lists
+            .iter()
+            .flat_map(|list| list.phrases.iter().map(|p| (p.text.as_str(), p.weight)))
+            .chain(self.class_groups.iter().flat_map(|group| {
+                group.entries.iter().flat_map(|entry| {
+                    std::iter::once((entry.name.as_str(), entry.weight))
+                        .chain(entry.synonyms.iter().map(|s| (s.as_str(), None)))
+                })
+            }));
+
+        let mut count = 0;
+        for (text, weight) in phrases {
+            count += 1;
+            if text.trim().is_empty() {
+                return Err(invalid("a phrase is empty"));
+            }
+            if text.chars().count() > MAX_PHRASE_LENGTH {
+                return Err(invalid(&format!(
+                    "the phrase '{text}' is longer than {MAX_PHRASE_LENGTH} characters"
+                )));
+            }
+            if let Some(weight) = weight {
+                if !PHRASE_WEIGHT_RANGE.contains(&weight) {
+                    return Err(invalid(&format!(
+                        "the weight {weight} of '{text}' is not between 0.0 and 2.0"
+                    )));
+                }
+            }
+        }
+        if count > MAX_PHRASES {
+            return Err(invalid(&format!(
+                "{count} phrases are more than the {MAX_PHRASES} allowed"
+            )));
+        }
+
+        if self
+            .reference_grammars
+            .iter()
+            .any(|id| id.trim().is_empty())
+        {
+            return Err(invalid("a reference grammar id is empty"));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        let mut dgi = json!({});
+
+        if !self.reference_grammars.is_empty() {
+            dgi["ReferenceGrammars"] = json!(self.reference_grammars);
+        }
+
+        let groups: Vec<Value> = self
+            .phrase_lists
+            .iter()
+            .map(PhraseList::to_json)
+            .chain(self.class_groups.iter().map(ClassGroup::to_json))
+            .collect();
+        if !groups.is_empty() {
+            dgi["Groups"] = json!(groups);
+        }
+
+        dgi
+    }
+}
+
+fn invalid(reason: &str) -> crate::Error {
+    crate::Error::ConfigError(format!("Invalid grammar: {reason}."))
+}
+
+/// A list of phrases, with an optional weight.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PhraseList {
+    pub(crate) name: Option<String>,
+    pub(crate) phrases: Vec<WeightedPhrase>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WeightedPhrase {
+    pub(crate) text: String,
+    pub(crate) weight: Option<f64>,
+}
+
+impl PhraseList {
+    /// Create the list from the given phrases.
+    pub fn new<S: Into<String>>(phrases: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            name: None,
+            phrases: phrases
+                .into_iter()
+                .map(|text| WeightedPhrase {
+                    text: text.into(),
+                    weight: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Set the name of the group.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the weight of all the phrases of the list, between `0.0` and `2.0`.
+    ///
+    /// A higher weight makes the phrases more likely to be recognized. Default: `1.0`.
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.phrases
+            .iter_mut()
+            .for_each(|phrase| phrase.weight = Some(weight));
+        self
+    }
+
+    /// Add a phrase with its own weight, between `0.0` and `2.0`.
+    pub fn with_weighted_phrase(mut self, text: impl Into<String>, weight: f64) -> Self {
+        self.phrases.push(WeightedPhrase {
+            text: text.into(),
+            weight: Some(weight),
+        });
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let items: Vec<Value> = self
+            .phrases
+            .iter()
+            .map(|phrase| {
+                let mut item = json!({ "Text": phrase.text });
+                if let Some(weight) = phrase.weight {
+                    item["Weight"] = json!(weight);
+                }
+                item
+            })
+            .collect();
+
+        group("Generic", self.name.as_deref(), items)
+    }
+}
+
+/// The class of the entries of a [`ClassGroup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityClass {
+    /// Names of people, like the contacts of a user.
+    People,
+}
+
+impl EntityClass {
+    fn as_str(self) -> &'static str {
+        match self {
+            EntityClass::People => "People",
+        }
+    }
+}
+
+/// A group of entries of the same class, to personalize the recognition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassGroup {
+    pub(crate) class: EntityClass,
+    pub(crate) name: Option<String>,
+    pub(crate) entries: Vec<ClassEntry>,
+}
+
+impl ClassGroup {
+    /// Create a group of people names.
+    pub fn people(entries: Vec<ClassEntry>) -> Self {
+        Self {
+            class: EntityClass::People,
+            name: None,
+            entries,
+        }
+    }
+
+    /// Set the name of the group.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let items = self.entries.iter().map(ClassEntry::to_json).collect();
+        group(self.class.as_str(), self.name.as_deref(), items)
+    }
+}
+
+/// An entry of a [`ClassGroup`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ClassEntry {
+    pub(crate) name: String,
+    pub(crate) first: Option<String>,
+    pub(crate) middle: Option<String>,
+    pub(crate) last: Option<String>,
+    pub(crate) synonyms: Vec<String>,
+    pub(crate) weight: Option<f64>,
+}
+
+impl ClassEntry {
+    /// Create the entry with its full name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the first, middle and last names of a person.
+    pub fn with_name_parts(
+        mut self,
+        first: Option<&str>,
+        middle: Option<&str>,
+        last: Option<&str>,
+    ) -> Self {
+        self.first = first.map(String::from);
+        self.middle = middle.map(String::from);
+        self.last = last.map(String::from);
+        self
+    }
+
+    /// Set the other ways to say the entry.
+    pub fn with_synonyms<S: Into<String>>(mut self, synonyms: impl IntoIterator<Item = S>) -> Self {
+        self.synonyms = synonyms.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the weight of the entry, between `0.0` and `2.0`.
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut item = json!({ "Name": self.name });
+        if let Some(first) = &self.first {
+            item["First"] = json!(first);
+        }
+        if let Some(middle) = &self.middle {
+            item["Middle"] = json!(middle);
+        }
+        if let Some(last) = &self.last {
+            item["Last"] = json!(last);
+        }
+        if !self.synonyms.is_empty() {
+            item["Synonyms"] = json!(self.synonyms);
+        }
+        if let Some(weight) = self.weight {
+            item["Weight"] = json!(weight);
+        }
+        item
+    }
+}
+
+fn group(group_type: &str, name: Option<&str>, items: Vec<Value>) -> Value {
+    let mut group = json!({ "Type": group_type });
+    if let Some(name) = name {
+        group["Name"] = json!(name);
+    }
+    group["Items"] = json!(items);
+    group
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_of(grammar: Grammar) -> String {
+        match grammar.validate() {
+            Err(crate::Error::ConfigError(message)) => message,
+            other => panic!("expected an error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_valid_grammar() {
+        let grammar = Grammar::new()
+            .with_phrase_list(PhraseList::new(vec!["Contoso"; MAX_PHRASES - 1]).with_weight(2.0))
+            .with_class_group(ClassGroup::people(vec![ClassEntry::new("Jessie Doe")]));
+
+        assert!(grammar.validate().is_ok());
+        assert!(Grammar::new().validate().is_ok());
+    }
+
+    #[test]
+    fn test_too_many_phrases() {
+        let grammar = Grammar::new()
+            .with_phrase_list(PhraseList::new(vec!["Contoso"; MAX_PHRASES - 1]))
+            .with_class_group(ClassGroup::people(vec![
+                ClassEntry::new("Jessie Doe").with_synonyms(vec!["JD"])
+            ]));
+
+        assert_eq!(
+            error_of(grammar),
+            "Invalid grammar: 501 phrases are more than the 500 allowed."
+        );
+    }
+
+    #[test]
+    fn test_phrase_length() {
+        let long = "a".repeat(MAX_PHRASE_LENGTH + 1);
+        let grammar = Grammar::new().with_phrase_list(PhraseList::new(vec![long.as_str()]));
+        assert!(error_of(grammar).contains("is longer than 100 characters"));
+
+        let grammar = Grammar::new().with_phrase_list(PhraseList::new(vec![" "]));
+        assert_eq!(error_of(grammar), "Invalid grammar: a phrase is empty.");
+    }
+
+    #[test]
+    fn test_weight_range() {
+        let grammar = Grammar::new().with_phrase_list(
+            PhraseList::new(vec!["Contoso"]).with_weighted_phrase("Fabrikam", 2.5),
+        );
+        assert_eq!(
+            error_of(grammar),
+            "Invalid grammar: the weight 2.5 of 'Fabrikam' is not between 0.0 and 2.0."
+        );
+
+        let grammar = Grammar::new()
+            .with_class_group(ClassGroup::people(vec![
+                ClassEntry::new("Jessie Doe").with_weight(-1.0)
+            ]));
+        assert!(grammar.validate().is_err());
+    }
+
+    #[test]
+    fn test_group_names() {
+        let grammar = Grammar::new()
+            .with_phrase_list(PhraseList::new(vec!["Contoso"]).with_name("companies"))
+            .with_class_group(ClassGroup::people(vec![]).with_name("companies"));
+        assert_eq!(
+            error_of(grammar),
+            "Invalid grammar: the group name 'companies' is duplicated."
+        );
+    }
+
+    #[test]
+    fn test_reference_grammar_id() {
+        let grammar = Grammar::new().with_reference_grammar("");
+        assert_eq!(
+            error_of(grammar),
+            "Invalid grammar: a reference grammar id is empty."
+        );
+    }
+}
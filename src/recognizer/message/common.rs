@@ -1,3 +1,4 @@
+use crate::recognizer::NoMatchReason;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, Clone)]
@@ -14,6 +15,7 @@ pub(crate) enum RecognitionStatus {
     NoMatch,
     InitialSilenceTimeout,
     BabbleTimeout,
+    KeywordNotRecognized,
     Error,
     EndOfDictation,
     TooManyRequests,
@@ -35,6 +37,18 @@ impl From<&RecognitionStatus> for Option<crate::Error> {
     }
 }
 
+impl From<&RecognitionStatus> for Option<NoMatchReason> {
+    fn from(value: &RecognitionStatus) -> Option<NoMatchReason> {
+        match value {
+            RecognitionStatus::NoMatch => Some(NoMatchReason::NotRecognized),
+            RecognitionStatus::InitialSilenceTimeout => Some(NoMatchReason::InitialSilenceTimeout),
+            RecognitionStatus::BabbleTimeout => Some(NoMatchReason::InitialBabbleTimeout),
+            RecognitionStatus::KeywordNotRecognized => Some(NoMatchReason::KeywordNotRecognized),
+            _ => None,
+        }
+    }
+}
+
 #[allow(dead_code)]
 impl RecognitionStatus {
     pub(crate) fn is_cancelled(&self) -> bool {
@@ -54,6 +68,7 @@ impl RecognitionStatus {
             RecognitionStatus::NoMatch
                 | RecognitionStatus::InitialSilenceTimeout
                 | RecognitionStatus::BabbleTimeout
+                | RecognitionStatus::KeywordNotRecognized
         )
     }
 
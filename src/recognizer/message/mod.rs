@@ -4,6 +4,7 @@ mod speech_hypothesis;
 mod speech_phrase;
 mod speech_start_detected;
 
+pub(crate) use common::*;
 pub(crate) use speech_end_detected::*;
 pub(crate) use speech_hypothesis::*;
 pub(crate) use speech_phrase::*;
@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use crate::recognizer::message::common::{Language, RecognitionStatus};
-use crate::recognizer::{Duration, Offset};
+use crate::recognizer::{Duration, ErrorType, Offset};
 use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -12,6 +12,8 @@ pub(crate) struct SpeechPhrase {
     pub(crate) offset: Option<Offset>,
     #[serde(rename = "Duration")]
     pub(crate) duration: Option<Duration>,
+    #[serde(rename = "PrimaryLanguage")]
+    pub(crate) primary_language: Option<Language>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -39,7 +41,7 @@ pub(crate) struct DetailedSpeechPhrase {
 #[derive(Debug, Clone, Deserialize)]
 pub(crate) struct Phrase {
     #[serde(rename = "Confidence")]
-    pub(crate) confidence: Option<String>,
+    pub(crate) confidence: Option<f64>,
     #[serde(rename = "Lexical")]
     pub(crate) lexical: String,
     #[serde(rename = "ITN")]
@@ -54,6 +56,18 @@ pub(crate) struct Phrase {
     pub(crate) words: Option<Vec<Word>>,
     #[serde(rename = "DisplayWords")]
     pub(crate) display_words: Option<Vec<Word>>,
+    #[serde(rename = "PronunciationAssessment")]
+    pub(crate) pronunciation_assessment: Option<PronunciationScores>,
+}
+
+impl Phrase {
+    /// The text to display, with the inverse text normalization when the display text is missing.
+    pub(crate) fn text(&self) -> &str {
+        self.display
+            .as_deref()
+            .or(self.display_text.as_deref())
+            .unwrap_or(&self.itn)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -64,4 +78,36 @@ pub(crate) struct Word {
     pub(crate) offset: Offset,
     #[serde(rename = "Duration")]
     pub(crate) duration: Duration,
+    #[serde(rename = "PronunciationAssessment")]
+    pub(crate) pronunciation_assessment: Option<PronunciationScores>,
+    #[serde(rename = "Phonemes")]
+    pub(crate) phonemes: Option<Vec<Phoneme>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Phoneme {
+    #[serde(rename = "Phoneme")]
+    pub(crate) phoneme: String,
+    #[serde(rename = "Offset")]
+    pub(crate) offset: Option<Offset>,
+    #[serde(rename = "Duration")]
+    pub(crate) duration: Option<Duration>,
+    #[serde(rename = "PronunciationAssessment")]
+    pub(crate) pronunciation_assessment: Option<PronunciationScores>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(crate) struct PronunciationScores {
+    #[serde(rename = "AccuracyScore")]
+    pub(crate) accuracy_score: Option<f64>,
+    #[serde(rename = "FluencyScore")]
+    pub(crate) fluency_score: Option<f64>,
+    #[serde(rename = "CompletenessScore")]
+    pub(crate) completeness_score: Option<f64>,
+    #[serde(rename = "PronScore")]
+    pub(crate) pron_score: Option<f64>,
+    #[serde(rename = "ProsodyScore")]
+    pub(crate) prosody_score: Option<f64>,
+    #[serde(rename = "ErrorType")]
+    pub(crate) error_type: Option<ErrorType>,
 }
@@ -40,19 +40,45 @@
 //! }
 //!
 
+pub(crate) mod audio_converter;
 mod audio_format;
+mod audio_input_stream;
 mod callback;
-mod client;
+mod captioning;
+mod channels;
+pub(crate) mod client;
 mod config;
+mod delta;
 mod event;
+mod grammar;
 mod language;
-mod message;
-mod session;
-mod utils;
+pub(crate) mod message;
+mod pacing;
+mod pronunciation_assessment;
+mod recognition;
+mod replay;
+mod result_filter;
+pub(crate) mod session;
+mod transcript;
+pub(crate) mod utils;
+mod voice_activity;
+mod wav;
 
 pub use audio_format::*;
+pub use audio_input_stream::*;
 pub use callback::*;
+pub use captioning::*;
+pub use channels::*;
 pub use client::*;
 pub use config::*;
+pub use delta::*;
 pub use event::*;
+pub use grammar::*;
 pub use language::*;
+pub use pacing::*;
+pub use pronunciation_assessment::*;
+pub use recognition::*;
+pub use result_filter::*;
+pub use transcript::*;
+pub use voice_activity::*;
+pub use wav::*;
@@ -0,0 +1,171 @@
+use crate::recognizer::WavSpec;
+use tokio::time::Instant;
+
+/// The configuration of the pacing of the audio sent to the service.
+///
+/// Without pacing, the audio is sent as fast as it is read: the service throttles large files,
+/// and the recognition can time out. The pacing is derived from the byte rate of the audio,
+/// so it is only available for PCM audio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioPacing {
+    pub(crate) speed: f64,
+    pub(crate) burst: std::time::Duration,
+}
+
+impl Default for AudioPacing {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            burst: std::time::Duration::from_secs(5),
+        }
+    }
+}
+
+impl AudioPacing {
+    /// Send the audio at the given multiple of real time.
+    ///
+    /// `1.0` is real time, `2.0` is twice faster.
+    pub fn new(speed: f64) -> Self {
+        Self {
+            speed,
+            ..Default::default()
+        }
+    }
+
+    /// Set the amount of audio sent without pacing, at the beginning. Default: 5 seconds.
+    pub fn with_burst(mut self, burst: std::time::Duration) -> Self {
+        self.burst = burst;
+        self
+    }
+}
+
+/// Delays the audio to send it at the configured pace.
+#[derive(Debug)]
+pub(crate) struct Pacer {
+    /// The bytes per second to send.
+    rate: f64,
+    burst_bytes: f64,
+    sent: u64,
+    started_at: Option<Instant>,
+}
+
+impl Pacer {
+    /// Create the pacer for the given spec.
+    ///
+    /// Returns `None` if the speed is not a positive number.
+    pub(crate) fn new(pacing: &AudioPacing, spec: WavSpec) -> Option<Self> {
+        if !(pacing.speed > 0.0 && pacing.speed.is_finite()) {
+            return None;
+        }
+
+        Some(Self {
+            rate: spec.byte_rate() as f64 * pacing.speed,
+            burst_bytes: spec.byte_rate() as f64 * pacing.burst.as_secs_f64(),
+            sent: 0,
+            started_at: None,
+        })
+    }
+
+    /// The instant when the given amount of bytes can be sent, or `None` if they can be sent now.
+    pub(crate) fn send_at(&mut self, bytes: usize) -> Option<Instant> {
+        let started_at = *self.started_at.get_or_insert_with(Instant::now);
+        self.delay(bytes, started_at.elapsed())
+            .map(|delay| Instant::now() + delay)
+    }
+
+    /// Record that the bytes were sent.
+    pub(crate) fn on_sent(&mut self, bytes: usize) {
+        self.sent += bytes as u64;
+    }
+
+    /// The time to wait before sending the bytes, after `elapsed` since the first ones.
+    fn delay(&self, bytes: usize, elapsed: std::time::Duration) -> Option<std::time::Duration> {
+        let paced = (self.sent + bytes as u64) as f64 - self.burst_bytes;
+        if paced <= 0.0 {
+            return None;
+        }
+
+        let due = std::time::Duration::from_secs_f64(paced / self.rate);
+        due.checked_sub(elapsed).filter(|delay| !delay.is_zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::audio_converter::TARGET_SPEC;
+    use std::time::Duration;
+
+    // 16 kHz 16-bit mono is 32000 bytes per second.
+
+    #[test]
+    fn test_burst_is_not_paced() {
+        let mut pacer = Pacer::new(&AudioPacing::new(1.0), TARGET_SPEC).unwrap();
+        pacer.sent = 5 * 32000 - 4000;
+
+        assert_eq!(pacer.delay(4000, Duration::ZERO), None);
+        assert_eq!(
+            pacer.delay(8000, Duration::ZERO),
+            Some(Duration::from_millis(125))
+        );
+    }
+
+    #[test]
+    fn test_real_time_pace() {
+        let mut pacer = Pacer::new(
+            &AudioPacing::new(1.0).with_burst(Duration::ZERO),
+            TARGET_SPEC,
+        )
+        .unwrap();
+        pacer.sent = 32000;
+
+        // One second of audio was sent: the next 500 ms are due at 1.5 s.
+        assert_eq!(
+            pacer.delay(16000, Duration::from_secs(1)),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(pacer.delay(16000, Duration::from_millis(1500)), None);
+        assert_eq!(pacer.delay(16000, Duration::from_secs(2)), None);
+    }
+
+    #[test]
+    fn test_multiple_of_real_time() {
+        let mut pacer = Pacer::new(
+            &AudioPacing::new(4.0).with_burst(Duration::ZERO),
+            TARGET_SPEC,
+        )
+        .unwrap();
+        pacer.sent = 32000 * 3;
+
+        // Four seconds of audio are sent in one second.
+        assert_eq!(
+            pacer.delay(32000, Duration::ZERO),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn test_invalid_speed() {
+        assert!(Pacer::new(&AudioPacing::new(0.0), TARGET_SPEC).is_none());
+        assert!(Pacer::new(&AudioPacing::new(f64::NAN), TARGET_SPEC).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pace_waits() {
+        let mut pacer = Pacer::new(
+            &AudioPacing::new(10.0).with_burst(Duration::ZERO),
+            TARGET_SPEC,
+        )
+        .unwrap();
+
+        let start = Instant::now();
+        // 100 ms of audio each, at 10 times real time.
+        for _ in 0..5 {
+            if let Some(send_at) = pacer.send_at(3200) {
+                tokio::time::sleep_until(send_at).await;
+            }
+            pacer.on_sent(3200);
+        }
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+}
@@ -0,0 +1,338 @@
+use crate::recognizer::message::{Phrase, Word};
+use crate::recognizer::session::Session;
+use crate::recognizer::{Duration, Offset};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// The configuration for the pronunciation assessment.
+///
+/// The pronunciation assessment evaluates the speech pronunciation against the reference text.
+/// When set on the recognizer [`Config`](crate::recognizer::Config), the output format is forced to detailed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PronunciationAssessmentConfig {
+    pub(crate) reference_text: String,
+    pub(crate) grading_system: GradingSystem,
+    pub(crate) granularity: Granularity,
+    pub(crate) enable_miscue: bool,
+    pub(crate) enable_prosody_assessment: bool,
+}
+
+impl PronunciationAssessmentConfig {
+    /// Create a new pronunciation assessment configuration for the given reference text.
+    ///
+    /// If the reference text is empty, the assessment is done in unscripted mode.
+    pub fn new(reference_text: impl Into<String>) -> Self {
+        Self {
+            reference_text: reference_text.into(),
+            grading_system: GradingSystem::default(),
+            granularity: Granularity::default(),
+            enable_miscue: false,
+            enable_prosody_assessment: false,
+        }
+    }
+
+    /// Set the point system for the score calibration.
+    pub fn with_grading_system(mut self, grading_system: GradingSystem) -> Self {
+        self.grading_system = grading_system;
+        self
+    }
+
+    /// Set the evaluation granularity.
+    pub fn with_granularity(mut self, granularity: Granularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// Enable the miscue calculation.
+    ///
+    /// The pronounced words are compared to the reference text,
+    /// and marked with omission/insertion based on the comparison.
+    pub fn enable_miscue(mut self) -> Self {
+        self.enable_miscue = true;
+        self
+    }
+
+    /// Enable the prosody assessment.
+    ///
+    /// Prosody assesses the naturalness of the speech: stress, intonation, speaking speed and rhythm.
+    pub fn enable_prosody_assessment(mut self) -> Self {
+        self.enable_prosody_assessment = true;
+        self
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        let mut params = json!({
+            "referenceText": self.reference_text,
+            "gradingSystem": self.grading_system,
+            "granularity": self.granularity,
+            "dimension": "Comprehensive",
+            "enableMiscue": self.enable_miscue,
+        });
+
+        if self.enable_prosody_assessment {
+            params["enableProsodyAssessment"] = json!(true);
+        }
+
+        params
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+/// The point system for the pronunciation score calibration.
+pub enum GradingSystem {
+    /// Five point calibration.
+    FivePoint,
+    /// Hundred mark calibration.
+    #[default]
+    HundredMark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+/// The granularity of the pronunciation assessment.
+pub enum Granularity {
+    /// Shows the score on the full text, word and phoneme level.
+    #[default]
+    Phoneme,
+    /// Shows the score on the full text and word level.
+    Word,
+    /// Shows the score on the full text level only.
+    FullText,
+}
+
+/// The pronunciation assessment of a recognized phrase.
+///
+/// Scores are calibrated with the configured [`GradingSystem`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PronunciationAssessment {
+    /// Pronunciation accuracy of the speech.
+    pub accuracy_score: f64,
+    /// Overall score of the pronunciation quality of the speech.
+    pub pronunciation_score: Option<f64>,
+    /// Fluency of the speech.
+    pub fluency_score: Option<f64>,
+    /// Completeness of the speech, compared to the reference text.
+    pub completeness_score: Option<f64>,
+    /// Prosody of the speech. Only present when the prosody assessment is enabled.
+    pub prosody_score: Option<f64>,
+    /// The assessment of each word. Empty when the granularity is [`Granularity::FullText`].
+    pub words: Vec<WordAssessment>,
+}
+
+/// The pronunciation assessment of a single word.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WordAssessment {
+    /// The word.
+    pub word: String,
+    /// The offset of the word, from the start of the audio.
+    pub offset: Offset,
+    /// The duration of the word.
+    pub duration: Duration,
+    /// Pronunciation accuracy of the word.
+    pub accuracy_score: Option<f64>,
+    /// Whether the word is omitted, inserted or badly pronounced, compared to the reference text.
+    pub error_type: ErrorType,
+    /// The assessment of each phoneme. Empty unless the granularity is [`Granularity::Phoneme`].
+    pub phonemes: Vec<PhonemeAssessment>,
+}
+
+/// The pronunciation assessment of a single phoneme.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhonemeAssessment {
+    /// The phoneme.
+    pub phoneme: String,
+    /// The offset of the phoneme, from the start of the audio.
+    pub offset: Offset,
+    /// The duration of the phoneme.
+    pub duration: Duration,
+    /// Pronunciation accuracy of the phoneme.
+    pub accuracy_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+/// The error type of an assessed word.
+pub enum ErrorType {
+    /// The word is correctly pronounced.
+    #[default]
+    None,
+    /// The word is in the reference text, but it was not pronounced.
+    Omission,
+    /// The word is not in the reference text, but it was pronounced.
+    Insertion,
+    /// The word is badly pronounced.
+    Mispronunciation,
+    /// The word is followed by an unexpected break.
+    UnexpectedBreak,
+    /// A break is missing before the word.
+    MissingBreak,
+    /// The word is pronounced with a monotone tone.
+    Monotone,
+    /// Any other error type returned by the service.
+    #[serde(other)]
+    Unknown,
+}
+
+impl PronunciationAssessment {
+    /// The offsets of the words and phonemes are converted from the current turn of the session.
+    pub(crate) fn from_phrase(phrase: &Phrase, session: &Session) -> Option<Self> {
+        let scores = phrase.pronunciation_assessment.as_ref()?;
+
+        Some(Self {
+            accuracy_score: scores.accuracy_score.unwrap_or_default(),
+            pronunciation_score: scores.pron_score,
+            fluency_score: scores.fluency_score,
+            completeness_score: scores.completeness_score,
+            prosody_score: scores.prosody_score,
+            words: phrase
+                .words
+                .as_ref()
+                .map(|words| {
+                    words
+                        .iter()
+                        .map(|word| WordAssessment::from_word(word, session))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+}
+
+impl WordAssessment {
+    fn from_word(word: &Word, session: &Session) -> Self {
+        let assessment = word.pronunciation_assessment.clone().unwrap_or_default();
+
+        Self {
+            word: word.word.clone(),
+            offset: session.audio_offset_at(word.offset),
+            duration: word.duration,
+            accuracy_score: assessment.accuracy_score,
+            error_type: assessment.error_type.unwrap_or_default(),
+            phonemes: word
+                .phonemes
+                .as_ref()
+                .map(|phonemes| {
+                    phonemes
+                        .iter()
+                        .map(|p| PhonemeAssessment {
+                            phoneme: p.phoneme.clone(),
+                            offset: session.audio_offset_at(p.offset.unwrap_or_default()),
+                            duration: p.duration.unwrap_or_default(),
+                            accuracy_score: p
+                                .pronunciation_assessment
+                                .as_ref()
+                                .and_then(|a| a.accuracy_score),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::message::DetailedSpeechPhrase;
+    use crate::Ticks;
+
+    #[test]
+    fn test_config_to_json() {
+        let config = PronunciationAssessmentConfig::new("Hello world")
+            .with_grading_system(GradingSystem::FivePoint)
+            .with_granularity(Granularity::Word)
+            .enable_miscue()
+            .enable_prosody_assessment();
+
+        assert_eq!(
+            config.to_json(),
+            json!({
+                "referenceText": "Hello world",
+                "gradingSystem": "FivePoint",
+                "granularity": "Word",
+                "dimension": "Comprehensive",
+                "enableMiscue": true,
+                "enableProsodyAssessment": true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_detailed_phrase() {
+        let json = r#"{
+            "RecognitionStatus": "Success",
+            "Offset": 500000,
+            "Duration": 9300000,
+            "DisplayText": "Hello world.",
+            "NBest": [{
+                "Confidence": 0.98,
+                "Lexical": "hello world",
+                "ITN": "hello world",
+                "MaskedITN": "hello world",
+                "Display": "Hello world.",
+                "PronunciationAssessment": {
+                    "AccuracyScore": 92.0,
+                    "FluencyScore": 100.0,
+                    "CompletenessScore": 100.0,
+                    "PronScore": 95.2,
+                    "ProsodyScore": 81.5
+                },
+                "Words": [{
+                    "Word": "hello",
+                    "Offset": 500000,
+                    "Duration": 4500000,
+                    "PronunciationAssessment": { "AccuracyScore": 84.0, "ErrorType": "Mispronunciation" },
+                    "Phonemes": [
+                        { "Phoneme": "h", "Offset": 500000, "Duration": 700000, "PronunciationAssessment": { "AccuracyScore": 100.0 } },
+                        { "Phoneme": "ɛ", "Offset": 1200000, "Duration": 800000, "PronunciationAssessment": { "AccuracyScore": 60.0 } }
+                    ]
+                }, {
+                    "Word": "world",
+                    "Offset": 5100000,
+                    "Duration": 4700000,
+                    "PronunciationAssessment": { "AccuracyScore": 100.0, "ErrorType": "None" }
+                }]
+            }]
+        }"#;
+
+        // The turn started 1s after the beginning of the audio.
+        let session = Session::new();
+        session.resume(Ticks::new(10_000_000));
+
+        let phrase: DetailedSpeechPhrase = serde_json::from_str(json).unwrap();
+        let assessment = PronunciationAssessment::from_phrase(&phrase.n_best[0], &session).unwrap();
+
+        assert_eq!(assessment.accuracy_score, 92.0);
+        assert_eq!(assessment.pronunciation_score, Some(95.2));
+        assert_eq!(assessment.fluency_score, Some(100.0));
+        assert_eq!(assessment.completeness_score, Some(100.0));
+        assert_eq!(assessment.prosody_score, Some(81.5));
+        assert_eq!(assessment.words.len(), 2);
+        assert_eq!(assessment.words[0].word, "hello");
+        assert_eq!(assessment.words[0].offset, Ticks::new(10_500_000));
+        assert_eq!(assessment.words[0].duration, Ticks::new(4_500_000));
+        assert_eq!(assessment.words[0].error_type, ErrorType::Mispronunciation);
+        assert_eq!(assessment.words[0].phonemes.len(), 2);
+        assert_eq!(assessment.words[0].phonemes[1].phoneme, "ɛ");
+        assert_eq!(
+            assessment.words[0].phonemes[1].offset,
+            Ticks::new(11_200_000)
+        );
+        assert_eq!(assessment.words[0].phonemes[1].accuracy_score, Some(60.0));
+        assert_eq!(assessment.words[1].offset, Ticks::new(15_100_000));
+        assert_eq!(assessment.words[1].error_type, ErrorType::None);
+        assert!(assessment.words[1].phonemes.is_empty());
+    }
+
+    #[test]
+    fn test_from_phrase_without_assessment() {
+        let json = r#"{
+            "NBest": [{ "Lexical": "hello", "ITN": "hello", "MaskedITN": "hello" }]
+        }"#;
+
+        let phrase: DetailedSpeechPhrase = serde_json::from_str(json).unwrap();
+        assert!(PronunciationAssessment::from_phrase(&phrase.n_best[0], &Session::new()).is_none());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::recognizer::client::AudioControl;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use pin_project_lite::pin_project;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Notify};
+use tokio_stream::Stream;
+
+pin_project! {
+    /// A running recognition.
+    ///
+    /// This is the stream of events returned by [`Client::recognize`](crate::recognizer::Client::recognize)
+    /// and [`translator::Client::translate`](crate::translator::Client::translate).
+    /// Use [`stop`](Recognition::stop) or [`abort`](Recognition::abort) to end the recognition
+    /// before the audio stream ends, or get a [`RecognitionHandle`] to do it from another task.
+    #[must_use = "streams do nothing unless polled"]
+    pub struct Recognition<S> {
+        #[pin]
+        events: S,
+        handle: RecognitionHandle,
+    }
+}
+
+impl<S> Recognition<S> {
+    pub(crate) fn new(events: S, handle: RecognitionHandle) -> Self {
+        Self { events, handle }
+    }
+
+    /// Get a handle to control the recognition from another task.
+    pub fn handle(&self) -> RecognitionHandle {
+        self.handle.clone()
+    }
+
+    /// See [`RecognitionHandle::stop`].
+    pub async fn stop(&self) {
+        self.handle.stop().await
+    }
+
+    /// See [`RecognitionHandle::abort`].
+    pub fn abort(&self) {
+        self.handle.abort()
+    }
+}
+
+impl<S> Stream for Recognition<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().events.poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.events.size_hint()
+    }
+}
+
+/// A handle to control a running recognition.
+///
+/// After the recognition is stopped or aborted, the client can be reused for the next recognition.
+#[derive(Clone, Debug)]
+pub struct RecognitionHandle {
+    /// The audio task of each recognized channel.
+    control: Vec<mpsc::Sender<AudioControl>>,
+    abort: Arc<Notify>,
+}
+
+impl RecognitionHandle {
+    pub(crate) fn new(control: mpsc::Sender<AudioControl>) -> Self {
+        Self {
+            control: vec![control],
+            abort: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Control the recognitions of all the channels together.
+    pub(crate) fn merge(handles: Vec<RecognitionHandle>) -> Self {
+        Self {
+            control: handles
+                .into_iter()
+                .flat_map(|handle| handle.control)
+                .collect(),
+            abort: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Stop the recognition gracefully.
+    ///
+    /// No more audio is read from the audio stream, and the end of audio is sent to the service.
+    /// The events of the audio already sent are still received, then the stream ends.
+    pub async fn stop(&self) {
+        // The audio task has already completed when the channel is closed.
+        for control in &self.control {
+            let _ = control.send(AudioControl::Stop).await;
+        }
+    }
+
+    /// Abort the recognition immediately.
+    ///
+    /// No more audio is read from the audio stream, and the stream of events ends without
+    /// waiting for the pending results.
+    pub fn abort(&self) {
+        for control in &self.control {
+            let _ = control.try_send(AudioControl::Stop);
+        }
+        self.abort.notify_one();
+    }
+
+    pub(crate) async fn aborted(&self) {
+        self.abort.notified().await
+    }
+}
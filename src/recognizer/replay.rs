@@ -0,0 +1,117 @@
+use std::collections::VecDeque;
+
+/// The audio sent since the last acknowledged position, to send it again after a reconnection.
+///
+/// The buffer is bounded: once the capacity is reached, the oldest audio is dropped.
+#[derive(Debug)]
+pub(crate) struct ReplayBuffer {
+    data: VecDeque<u8>,
+    /// The position, in the sent audio, of the first byte of the buffer.
+    start: u64,
+    capacity: usize,
+    /// The size of a frame: the audio is only dropped by whole frames.
+    alignment: usize,
+}
+
+impl ReplayBuffer {
+    pub(crate) fn new(capacity: usize, alignment: usize) -> Self {
+        Self {
+            data: VecDeque::new(),
+            start: 0,
+            capacity,
+            alignment: alignment.max(1),
+        }
+    }
+
+    /// Append the audio sent.
+    pub(crate) fn push(&mut self, data: &[u8]) {
+        self.data.extend(data);
+        if self.data.len() > self.capacity {
+            let overflow = self.data.len() - self.capacity;
+            // Round up to the next frame, without dropping more than the buffer.
+            let overflow = ((overflow + self.alignment - 1) / self.alignment * self.alignment)
+                .min(self.data.len());
+            self.drop_front(overflow);
+        }
+    }
+
+    /// Drop the audio before the given position of the sent audio.
+    pub(crate) fn acknowledge(&mut self, position: u64) {
+        let count = position
+            .saturating_sub(self.start)
+            .min(self.data.len() as u64);
+        self.drop_front(count as usize);
+    }
+
+    /// The position, in the sent audio, of the first buffered byte.
+    pub(crate) fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Whether no audio is kept, and the recognition can not be resumed.
+    pub(crate) fn is_disabled(&self) -> bool {
+        self.capacity == 0
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Copy the buffered audio, in chunks of at most the given size.
+    pub(crate) fn chunks(&self, size: usize) -> Vec<Vec<u8>> {
+        let (front, back) = self.data.as_slices();
+        [front, back]
+            .concat()
+            .chunks(size)
+            .map(<[u8]>::to_vec)
+            .collect()
+    }
+
+    fn drop_front(&mut self, count: usize) {
+        self.data.drain(..count);
+        self.start += count as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acknowledged_audio_is_dropped() {
+        let mut buffer = ReplayBuffer::new(100, 1);
+        buffer.push(&[1, 2, 3]);
+        buffer.push(&[4, 5]);
+        buffer.acknowledge(2);
+
+        assert_eq!(buffer.start(), 2);
+        assert_eq!(buffer.chunks(2), vec![vec![3, 4], vec![5]]);
+
+        // The positions already dropped, or not sent yet, are ignored.
+        buffer.acknowledge(1);
+        assert_eq!(buffer.start(), 2);
+        buffer.acknowledge(10);
+        assert_eq!(buffer.start(), 5);
+        assert!(buffer.is_empty());
+
+        buffer.push(&[6]);
+        assert_eq!(buffer.start(), 5);
+        assert_eq!(buffer.chunks(2), vec![vec![6]]);
+    }
+
+    #[test]
+    fn test_capacity_drops_whole_frames() {
+        let mut buffer = ReplayBuffer::new(5, 2);
+        buffer.push(&[1, 2, 3, 4]);
+        buffer.push(&[5, 6]);
+
+        assert_eq!(buffer.start(), 2);
+        assert_eq!(buffer.chunks(10), vec![vec![3, 4, 5, 6]]);
+
+        let mut disabled = ReplayBuffer::new(0, 2);
+        disabled.push(&[1, 2, 3]);
+        assert!(disabled.is_empty());
+        assert!(disabled.is_disabled());
+        assert_eq!(disabled.start(), 3);
+    }
+}
@@ -0,0 +1,238 @@
+use crate::recognizer::message::Phrase;
+use crate::recognizer::Grammar;
+use std::cmp::Ordering;
+
+/// How the recognized text is chosen among the alternatives of a phrase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlternateSelection {
+    /// The first alternative, in the order of the service.
+    #[default]
+    First,
+    /// The alternative with the highest confidence.
+    BestConfidence,
+    /// The alternative containing the most phrases of the phrase lists, then the one with the
+    /// highest confidence.
+    ///
+    /// The phrases are matched as whole words, ignoring the case, against the lexical form of the
+    /// alternatives.
+    PreferPhrases,
+}
+
+/// The filtering of the recognized phrases, based on the alternatives of the detailed output.
+///
+/// A phrase whose alternatives are all below the minimum confidence is sent as an
+/// [`Event::UnMatch`](crate::recognizer::Event::UnMatch) event, with the
+/// [`LowConfidence`](crate::recognizer::NoMatchReason::LowConfidence) reason.
+/// The alternatives without a confidence are always kept.
+///
+/// ```
+/// use azure_speech::recognizer::{AlternateSelection, Config, ResultFilter};
+///
+/// let config = Config::default().set_result_filter(
+///     ResultFilter::new()
+///         .with_min_confidence(0.6)
+///         .with_selection(AlternateSelection::PreferPhrases),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResultFilter {
+    pub(crate) min_confidence: Option<f64>,
+    pub(crate) selection: AlternateSelection,
+}
+
+impl ResultFilter {
+    /// Keep all the phrases, and select the first alternative.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the minimum confidence, between `0.0` and `1.0`, of the selected alternative.
+    pub fn with_min_confidence(mut self, min_confidence: f64) -> Self {
+        self.min_confidence = Some(min_confidence);
+        self
+    }
+
+    /// Set how the alternative is chosen. Default: [`AlternateSelection::First`].
+    pub fn with_selection(mut self, selection: AlternateSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Select the alternative among the ones reaching the minimum confidence.
+    ///
+    /// Returns `None` when no alternative reaches the minimum confidence.
+    pub(crate) fn select<'a>(
+        &self,
+        alternatives: &'a [Phrase],
+        grammar: &Grammar,
+    ) -> Option<&'a Phrase> {
+        let mut candidates =
+            alternatives
+                .iter()
+                .filter(|phrase| match (self.min_confidence, phrase.confidence) {
+                    (Some(min), Some(confidence)) => confidence >= min,
+                    _ => true,
+                });
+
+        match self.selection {
+            AlternateSelection::First => candidates.next(),
+            AlternateSelection::BestConfidence => {
+                candidates.reduce(|best, phrase| match compare_confidence(phrase, best) {
+                    Ordering::Greater => phrase,
+                    _ => best,
+                })
+            }
+            AlternateSelection::PreferPhrases => {
+                let phrases: Vec<Vec<String>> = grammar
+                    .phrase_lists
+                    .iter()
+                    .flat_map(|list| list.phrases.iter())
+                    .map(|phrase| words(&phrase.text))
+                    .filter(|words| !words.is_empty())
+                    .collect();
+                let matches = |phrase: &Phrase| {
+                    let lexical = words(&phrase.lexical);
+                    phrases
+                        .iter()
+                        .filter(|p| {
+                            lexical
+                                .windows(p.len())
+                                .any(|window| window == p.as_slice())
+                        })
+                        .count()
+                };
+
+                candidates.reduce(|best, phrase| {
+                    match matches(phrase)
+                        .cmp(&matches(best))
+                        .then_with(|| compare_confidence(phrase, best))
+                    {
+                        Ordering::Greater => phrase,
+                        _ => best,
+                    }
+                })
+            }
+        }
+    }
+}
+
+/// The lowercase words of the text, without the punctuation around them.
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn compare_confidence(a: &Phrase, b: &Phrase) -> Ordering {
+    a.confidence
+        .unwrap_or_default()
+        .partial_cmp(&b.confidence.unwrap_or_default())
+        .unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::PhraseList;
+
+    fn alternatives() -> Vec<Phrase> {
+        serde_json::from_str(
+            r#"[
+                {"Confidence":0.6,"Lexical":"call jon","ITN":"call jon","MaskedITN":"call jon","Display":"Call Jon."},
+                {"Confidence":0.7,"Lexical":"call john","ITN":"call john","MaskedITN":"call john","Display":"Call John."},
+                {"Confidence":0.5,"Lexical":"call joan","ITN":"call joan","MaskedITN":"call joan","Display":"Call Joan."}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    fn select(filter: ResultFilter, grammar: &Grammar) -> Option<String> {
+        filter
+            .select(&alternatives(), grammar)
+            .map(|phrase| phrase.text().to_string())
+    }
+
+    #[test]
+    fn test_selection_strategies() {
+        let grammar = Grammar::new().with_phrase_list(PhraseList::new(["Joan"]));
+
+        assert_eq!(
+            select(ResultFilter::new(), &grammar),
+            Some("Call Jon.".to_string())
+        );
+        assert_eq!(
+            select(
+                ResultFilter::new().with_selection(AlternateSelection::BestConfidence),
+                &grammar
+            ),
+            Some("Call John.".to_string())
+        );
+        assert_eq!(
+            select(
+                ResultFilter::new().with_selection(AlternateSelection::PreferPhrases),
+                &grammar
+            ),
+            Some("Call Joan.".to_string())
+        );
+        // Without any phrase in the alternatives, the best confidence wins.
+        assert_eq!(
+            select(
+                ResultFilter::new().with_selection(AlternateSelection::PreferPhrases),
+                &Grammar::new()
+            ),
+            Some("Call John.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prefer_phrases_matches_whole_words() {
+        let alternatives: Vec<Phrase> = serde_json::from_str(
+            r#"[
+                {"Confidence":0.7,"Lexical":"call jonathan","ITN":"call jonathan","MaskedITN":"call jonathan","Display":"Call Jonathan."},
+                {"Confidence":0.6,"Lexical":"call jon now","ITN":"call jon now","MaskedITN":"call jon now","Display":"Call Jon now."}
+            ]"#,
+        )
+        .unwrap();
+        let filter = ResultFilter::new().with_selection(AlternateSelection::PreferPhrases);
+        let select = |phrases: &[&str]| {
+            filter
+                .select(
+                    &alternatives,
+                    &Grammar::new().with_phrase_list(PhraseList::new(phrases.to_vec())),
+                )
+                .map(|phrase| phrase.text().to_string())
+        };
+
+        assert_eq!(select(&["Jon"]), Some("Call Jon now.".to_string()));
+        assert_eq!(select(&["Jon, now!"]), Some("Call Jon now.".to_string()));
+        // A part of a word is not a match.
+        assert_eq!(select(&["Jonat"]), Some("Call Jonathan.".to_string()));
+    }
+
+    #[test]
+    fn test_min_confidence() {
+        let grammar = Grammar::new().with_phrase_list(PhraseList::new(["Joan"]));
+
+        assert_eq!(
+            select(ResultFilter::new().with_min_confidence(0.65), &grammar),
+            Some("Call John.".to_string())
+        );
+        assert_eq!(
+            select(
+                ResultFilter::new()
+                    .with_min_confidence(0.55)
+                    .with_selection(AlternateSelection::PreferPhrases),
+                &grammar
+            ),
+            Some("Call John.".to_string())
+        );
+        assert_eq!(
+            select(ResultFilter::new().with_min_confidence(0.8), &grammar),
+            None
+        );
+    }
+}
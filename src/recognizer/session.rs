@@ -1,13 +1,22 @@
-use crate::recognizer::Offset;
+use crate::recognizer::{Duration, Offset, WavSpec};
 use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Default, Clone)]
 struct SessionInner {
     request_id: uuid::Uuid,
     is_audio_completed: bool,
+    /// The offset of the current turn, in the timeline of the sent audio.
     audio_offset: Offset,
+    /// The end of the audio acknowledged by the service, in the timeline of the sent audio:
+    /// the end of the last final phrase, or the start of the turn.
     recognition_offset: Offset,
+    /// The amount of audio data sent, across the turns.
+    bytes_sent: u64,
+    /// The amount of audio data sent, when the audio was last acknowledged.
+    acknowledged_bytes: u64,
     hypothesis_received: bool,
+    /// The audio withheld by the voice activity detection, in the timeline of the sent audio.
+    skipped_audio: Vec<(Offset, Duration)>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -25,12 +34,29 @@ impl Session {
         }
     }
 
-    pub(crate) fn refresh(&self) {
+    /// Start a new turn.
+    ///
+    /// The service offsets restart with the turn: the new turn starts after all the audio sent,
+    /// when its duration is known from the spec, or else at the end of the last final phrase.
+    pub(crate) fn refresh(&self, spec: Option<&WavSpec>) {
+        let audio_offset = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.acknowledged_bytes = inner.bytes_sent;
+            match spec {
+                Some(spec) => spec.offset_of(inner.bytes_sent),
+                None => inner.recognition_offset,
+            }
+        };
+        self.resume(audio_offset);
+    }
+
+    /// Start a new turn at the given offset of the sent audio, to send the audio again from there.
+    pub(crate) fn resume(&self, audio_offset: Offset) {
         let mut inner = self.inner.lock().unwrap();
         inner.request_id = uuid::Uuid::new_v4();
         inner.is_audio_completed = false;
-        inner.audio_offset = 0;
-        inner.recognition_offset = 0;
+        inner.audio_offset = audio_offset;
+        inner.recognition_offset = inner.recognition_offset.max(audio_offset);
         inner.hypothesis_received = false;
     }
 
@@ -39,14 +65,19 @@ impl Session {
         // todo: update telemetry..
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn on_phrase_recognized(&self, offset: Offset) {
+    /// Record the final phrase at the given offset of the current turn.
+    pub(crate) fn on_phrase_recognized(&self, offset: Offset, duration: Duration) {
         let mut inner = self.inner.lock().unwrap();
-        inner.recognition_offset += offset;
+        inner.recognition_offset = inner.audio_offset + offset + duration;
+        inner.acknowledged_bytes = inner.bytes_sent;
         inner.hypothesis_received = false;
     }
 
-    #[allow(dead_code)]
+    /// Record that the audio data was sent to the service.
+    pub(crate) fn on_audio_sent(&self, bytes: usize) {
+        self.inner.lock().unwrap().bytes_sent += bytes as u64;
+    }
+
     pub(crate) fn is_audio_completed(&self) -> bool {
         self.inner.lock().unwrap().is_audio_completed
     }
@@ -59,22 +90,115 @@ impl Session {
         self.inner.lock().unwrap().request_id
     }
 
+    #[cfg(test)]
     pub(crate) fn audio_offset(&self) -> Offset {
         self.inner.lock().unwrap().audio_offset
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn set_audio_offset(&self, audio_offset: Offset) {
-        self.inner.lock().unwrap().audio_offset = audio_offset;
+    /// Convert an offset of the current turn to the timeline of the original audio.
+    ///
+    /// The offsets of the service only cover the audio that was sent: the withheld audio is added back.
+    pub(crate) fn audio_offset_at(&self, offset: Offset) -> Offset {
+        let inner = self.inner.lock().unwrap();
+        let sent = inner.audio_offset + offset;
+        let skipped: Duration = inner
+            .skipped_audio
+            .iter()
+            .filter(|(at, _)| *at <= sent)
+            .map(|(_, duration)| duration)
+            .sum();
+        sent + skipped
+    }
+
+    /// Record that the audio was withheld at the given offset of the sent audio.
+    ///
+    /// The withheld audio is kept across the turns, as the offsets of the sent audio are.
+    pub(crate) fn on_audio_skipped(&self, at: Offset, duration: Duration) {
+        self.inner
+            .lock()
+            .unwrap()
+            .skipped_audio
+            .push((at, duration));
     }
 
-    #[allow(dead_code)]
     pub(crate) fn recognition_offset(&self) -> Offset {
         self.inner.lock().unwrap().recognition_offset
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn set_recognition_offset(&self, recognition_offset: Offset) {
-        self.inner.lock().unwrap().recognition_offset = recognition_offset;
+    pub(crate) fn acknowledged_bytes(&self) -> u64 {
+        self.inner.lock().unwrap().acknowledged_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::audio_converter::TARGET_SPEC;
+    use crate::Ticks;
+
+    #[test]
+    fn test_audio_offset_at_adds_skipped_audio() {
+        let session = Session::new();
+        session.on_audio_skipped(Ticks::new(0), Ticks::new(100));
+        session.on_audio_skipped(Ticks::new(500), Ticks::new(1000));
+
+        assert_eq!(session.audio_offset_at(Ticks::new(0)), Ticks::new(100));
+        assert_eq!(session.audio_offset_at(Ticks::new(499)), Ticks::new(599));
+        assert_eq!(session.audio_offset_at(Ticks::new(500)), Ticks::new(1600));
+
+        // The next turn starts after 3200 bytes, or 1000 ticks, of sent audio.
+        session.on_audio_sent(3200);
+        session.refresh(Some(&TARGET_SPEC));
+        assert_eq!(
+            session.audio_offset_at(Ticks::new(10)),
+            Ticks::new(1_001_110)
+        );
+    }
+
+    #[test]
+    fn test_turns_without_spec_start_after_the_last_phrase() {
+        let session = Session::new();
+        session.on_audio_sent(4096);
+        session.on_phrase_recognized(Ticks::new(100), Ticks::new(500));
+        session.refresh(None);
+        assert_eq!(session.audio_offset(), Ticks::new(600));
+        assert_eq!(session.audio_offset_at(Ticks::new(50)), Ticks::new(650));
+
+        session.on_phrase_recognized(Ticks::new(50), Ticks::new(200));
+        assert_eq!(session.recognition_offset(), Ticks::new(850));
+        session.refresh(None);
+        assert_eq!(session.audio_offset_at(Ticks::new(0)), Ticks::new(850));
+    }
+
+    #[test]
+    fn test_turns_with_spec_start_after_the_sent_audio() {
+        let session = Session::new();
+        session.on_phrase_recognized(Ticks::new(100), Ticks::new(500));
+        for _ in 0..3 {
+            session.on_audio_sent(4096);
+        }
+        session.refresh(Some(&TARGET_SPEC));
+        assert_eq!(
+            session.audio_offset_at(Ticks::new(50)),
+            Ticks::new(3_840_050)
+        );
+        // The audio of the ended turn is acknowledged.
+        assert_eq!(session.recognition_offset(), Ticks::new(3_840_000));
+        assert_eq!(session.acknowledged_bytes(), 4096 * 3);
+    }
+
+    #[test]
+    fn test_resume_from_the_acknowledged_audio() {
+        let session = Session::new();
+        let request_id = session.request_id();
+        session.on_audio_sent(4096);
+        session.on_phrase_recognized(Ticks::new(100), Ticks::new(500));
+        session.on_audio_sent(4096);
+        assert_eq!(session.acknowledged_bytes(), 4096);
+
+        session.resume(Ticks::new(600));
+        assert_ne!(session.request_id(), request_id);
+        assert_eq!(session.audio_offset_at(Ticks::new(50)), Ticks::new(650));
+        assert_eq!(session.recognition_offset(), Ticks::new(600));
     }
 }
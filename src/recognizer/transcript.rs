@@ -0,0 +1,561 @@
+use crate::recognizer::{Duration, Event, Language, Offset};
+use crate::Ticks;
+use serde_json::{json, Value};
+use std::fmt::Write;
+use tokio_stream::{Stream, StreamExt};
+
+/// A recognized phrase of a [`Transcript`].
+///
+/// Serialized with the same fields as [`Transcript::to_json`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TranscriptPhrase {
+    /// The recognized text.
+    pub text: String,
+    /// The offset of the phrase, in ticks of 100 nanoseconds.
+    pub offset: Offset,
+    /// The duration of the phrase, in ticks of 100 nanoseconds.
+    pub duration: Duration,
+    /// The language of the phrase, when detected.
+    pub language: Option<Language>,
+    /// The speaker of the phrase, when recognized.
+    pub speaker_id: Option<String>,
+    /// The audio channel of the phrase, when the channels are recognized separately.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub channel: Option<u16>,
+}
+
+/// The transcript of a recognition.
+///
+/// Gathers the [`Event::Recognized`] phrases, and exports them as text, JSON, SRT or WebVTT.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example(client: azure_speech::recognizer::Client) -> azure_speech::Result<()> {
+/// use azure_speech::recognizer::{CaptionOptions, Transcript};
+///
+/// let events = client.recognize_file("audio.wav").await?;
+/// let transcript = Transcript::from_stream(events).await?;
+///
+/// println!("{}", transcript.to_srt(&CaptionOptions::default()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transcript {
+    phrases: Vec<TranscriptPhrase>,
+}
+
+impl Transcript {
+    /// Create an empty transcript.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consume the stream of events, until it ends or an error is received.
+    pub async fn from_stream<S>(stream: S) -> crate::Result<Self>
+    where
+        S: Stream<Item = crate::Result<Event>>,
+    {
+        let mut stream = std::pin::pin!(stream);
+        let mut transcript = Self::new();
+        while let Some(event) = stream.next().await {
+            transcript.push(&event?);
+        }
+        Ok(transcript)
+    }
+
+    /// Add the event to the transcript.
+    ///
+    /// Only the [`Event::Recognized`] events with some text are kept.
+    pub fn push(&mut self, event: &Event) {
+        if let Event::Recognized(event) = event {
+            let recognized = event.result();
+            if recognized.text.trim().is_empty() {
+                return;
+            }
+
+            self.phrases.push(TranscriptPhrase {
+                text: recognized.text.trim().to_string(),
+                offset: event.offset(),
+                duration: event.duration(),
+                language: recognized
+                    .primary_language
+                    .as_ref()
+                    .map(|l| l.language.clone()),
+                speaker_id: recognized.speaker_id.clone(),
+                channel: event.channel(),
+            });
+        }
+    }
+
+    /// The phrases of the transcript.
+    pub fn phrases(&self) -> &[TranscriptPhrase] {
+        &self.phrases
+    }
+
+    /// Export the transcript as plain text, one phrase per line.
+    pub fn to_text(&self) -> String {
+        self.phrases
+            .iter()
+            .map(|p| p.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Export the transcript as JSON.
+    ///
+    /// The channel of the phrases is only set when the channels are recognized separately.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "phrases": self.phrases.iter().map(|p| {
+                let mut phrase = json!({
+                    "text": p.text,
+                    "offset": p.offset,
+                    "duration": p.duration,
+                    "language": p.language.as_ref().map(|l| l.to_string()),
+                    "speakerId": p.speaker_id,
+                });
+                if let Some(channel) = p.channel {
+                    phrase["channel"] = json!(channel);
+                }
+                phrase
+            }).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Export the transcript as SubRip subtitles.
+    pub fn to_srt(&self, options: &CaptionOptions) -> String {
+        let mut srt = String::new();
+        for (i, caption) in self.captions(options).iter().enumerate() {
+            let _ = write!(
+                srt,
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                format_timestamp(caption.start, ','),
+                format_timestamp(caption.end, ','),
+                caption.lines.join("\n")
+            );
+        }
+        srt
+    }
+
+    /// Export the transcript as WebVTT subtitles.
+    ///
+    /// The speaker, when recognized, is set as the voice of the cue. Otherwise, when the channels
+    /// are recognized separately, the voice is the channel.
+    pub fn to_webvtt(&self, options: &CaptionOptions) -> String {
+        let mut vtt = String::from("WEBVTT\n\n");
+        for caption in self.captions(options) {
+            let voice = caption
+                .speaker_id
+                .or_else(|| {
+                    caption
+                        .channel
+                        .map(|channel| format!("Channel {}", channel))
+                })
+                .map(|speaker| format!("<v {}>", speaker))
+                .unwrap_or_default();
+            let _ = write!(
+                vtt,
+                "{} --> {}\n{}{}\n\n",
+                format_timestamp(caption.start, '.'),
+                format_timestamp(caption.end, '.'),
+                voice,
+                caption.lines.join("\n")
+            );
+        }
+        vtt
+    }
+
+    fn captions(&self, options: &CaptionOptions) -> Vec<Caption> {
+        self.phrases
+            .iter()
+            .flat_map(|phrase| split_phrase(phrase, options))
+            .collect()
+    }
+}
+
+/// How the text is split in lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineSplit {
+    /// Break the lines between the words.
+    #[default]
+    Words,
+    /// Break the lines between the words, and start a new caption after each sentence.
+    Sentences,
+    /// Break the lines anywhere. For the languages written without spaces.
+    Characters,
+}
+
+/// The options of the SRT and WebVTT exports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptionOptions {
+    pub(crate) max_line_length: usize,
+    pub(crate) max_lines: usize,
+    pub(crate) max_caption_duration: std::time::Duration,
+    pub(crate) line_split: LineSplit,
+}
+
+impl Default for CaptionOptions {
+    fn default() -> Self {
+        Self {
+            max_line_length: 42,
+            max_lines: 2,
+            max_caption_duration: std::time::Duration::from_secs(6),
+            line_split: LineSplit::default(),
+        }
+    }
+}
+
+impl CaptionOptions {
+    /// Set the maximum number of characters of a line. Default: 42.
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length.max(1);
+        self
+    }
+
+    /// Set the maximum number of lines of a caption. Default: 2.
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines.max(1);
+        self
+    }
+
+    /// Set the maximum duration of a caption. Default: 6 seconds.
+    pub fn with_max_caption_duration(mut self, max_caption_duration: std::time::Duration) -> Self {
+        self.max_caption_duration = max_caption_duration;
+        self
+    }
+
+    /// Set how the text is split in lines. Default: [`LineSplit::Words`].
+    pub fn with_line_split(mut self, line_split: LineSplit) -> Self {
+        self.line_split = line_split;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Caption {
+    start: Offset,
+    end: Offset,
+    lines: Vec<String>,
+    speaker_id: Option<String>,
+    channel: Option<u16>,
+}
+
+/// Split the phrase in captions.
+///
+/// The phrase has no word timings: the duration is spread over the characters.
+fn split_phrase(phrase: &TranscriptPhrase, options: &CaptionOptions) -> Vec<Caption> {
+    let (tokens, separator): (Vec<String>, &str) = match options.line_split {
+        LineSplit::Characters => (
+            phrase
+                .text
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(String::from)
+                .collect(),
+            "",
+        ),
+        _ => (
+            phrase.text.split_whitespace().map(String::from).collect(),
+            " ",
+        ),
+    };
+
+    let length = |s: &str| s.chars().count();
+    let total: usize = tokens.iter().map(|t| length(t)).sum::<usize>()
+        + length(separator) * tokens.len().saturating_sub(1);
+    let ticks_per_char = phrase.duration.as_ticks() as f64 / total.max(1) as f64;
+    let at = |position: usize| {
+        phrase.offset + Ticks::new((position as f64 * ticks_per_char).round() as u64)
+    };
+    let max_ticks = Ticks::from(options.max_caption_duration);
+
+    let mut captions = vec![];
+    let mut lines: Vec<String> = vec![];
+    let mut line = String::new();
+    // Positions, in characters of the phrase, of the start of the caption and of the next token.
+    let mut start = 0;
+    let mut position = 0;
+
+    let mut flush = |lines: &mut Vec<String>, line: &mut String, start: &mut usize, end: usize| {
+        if !line.is_empty() {
+            lines.push(std::mem::take(line));
+        }
+        if !lines.is_empty() {
+            captions.push(Caption {
+                start: at(*start),
+                end: at(end),
+                lines: std::mem::take(lines),
+                speaker_id: phrase.speaker_id.clone(),
+                channel: phrase.channel,
+            });
+        }
+        *start = end;
+    };
+
+    for token in &tokens {
+        let separator = if line.is_empty() { "" } else { separator };
+        let token_end = position + length(separator) + length(token);
+
+        if !line.is_empty()
+            && length(&line) + length(separator) + length(token) > options.max_line_length
+        {
+            lines.push(std::mem::take(&mut line));
+        }
+        let caption_is_full = lines.len() >= options.max_lines;
+        let caption_is_long = at(token_end) - at(start) > max_ticks;
+        if (caption_is_full || caption_is_long) && (!lines.is_empty() || !line.is_empty()) {
+            flush(&mut lines, &mut line, &mut start, position);
+        }
+
+        if !line.is_empty() {
+            line.push_str(separator);
+        }
+        line.push_str(token);
+        position = token_end;
+
+        let ends_sentence = token.ends_with(['.', '?', '!', '。', '？', '！']);
+        if options.line_split == LineSplit::Sentences && ends_sentence {
+            flush(&mut lines, &mut line, &mut start, position);
+        }
+    }
+    flush(&mut lines, &mut line, &mut start, position);
+
+    // The last caption lasts until the end of the phrase.
+    if let Some(last) = captions.last_mut() {
+        last.end = phrase.offset + phrase.duration;
+    }
+    captions
+}
+
+/// Format the offset as `HH:MM:SS,mmm`, with the given separator of the milliseconds.
+fn format_timestamp(offset: Offset, separator: char) -> String {
+    let ms = offset.as_millis();
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::{
+        Confidence, NoMatchReason, PrimaryLanguage, Recognized, RecognizedEvent, SessionEvent,
+        UnMatchEvent,
+    };
+    use uuid::Uuid;
+
+    const SECOND: Ticks = Ticks::from_millis(1000);
+
+    fn recognized(text: &str, offset: Offset, duration: Duration) -> Event {
+        Event::Recognized(RecognizedEvent::new(
+            Uuid::nil(),
+            Recognized {
+                text: text.to_string(),
+                primary_language: Some(PrimaryLanguage::new(Language::EnUs, Confidence::High)),
+                speaker_id: None,
+                pronunciation_assessment: None,
+            },
+            offset,
+            duration,
+            String::new(),
+        ))
+    }
+
+    fn transcript(events: &[Event]) -> Transcript {
+        let mut transcript = Transcript::new();
+        events.iter().for_each(|e| transcript.push(e));
+        transcript
+    }
+
+    #[tokio::test]
+    async fn test_from_stream_keeps_recognized_phrases() {
+        let events = vec![
+            Ok(Event::SessionStarted(SessionEvent::new(Uuid::nil()))),
+            Ok(recognized("Hello world.", SECOND, SECOND * 2)),
+            Ok(Event::UnMatch(UnMatchEvent::new(
+                Uuid::nil(),
+                NoMatchReason::NotRecognized,
+                Ticks::ZERO,
+                Ticks::ZERO,
+                String::new(),
+            ))),
+            Ok(recognized(" ", SECOND * 4, SECOND)),
+            Ok(recognized("How are you?", SECOND * 5, SECOND)),
+            Ok(Event::SessionEnded(SessionEvent::new(Uuid::nil()))),
+        ];
+
+        let transcript = Transcript::from_stream(tokio_stream::iter(events))
+            .await
+            .unwrap();
+
+        assert_eq!(transcript.phrases().len(), 2);
+        assert_eq!(transcript.phrases()[0].language, Some(Language::EnUs));
+        assert_eq!(transcript.to_text(), "Hello world.\nHow are you?");
+        assert_eq!(
+            transcript.to_json(),
+            json!({"phrases": [
+                {"text": "Hello world.", "offset": SECOND, "duration": SECOND * 2, "language": "en-US", "speakerId": null},
+                {"text": "How are you?", "offset": SECOND * 5, "duration": SECOND, "language": "en-US", "speakerId": null},
+            ]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_from_stream_returns_errors() {
+        let events = vec![
+            Ok(recognized("Hello world.", Ticks::ZERO, SECOND)),
+            Err(crate::Error::RuntimeError("failed".to_string())),
+        ];
+
+        assert!(Transcript::from_stream(tokio_stream::iter(events))
+            .await
+            .is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let transcript = transcript(&[
+            recognized("Hello world.", SECOND, SECOND * 2),
+            recognized("How are you?", SECOND * 5, SECOND),
+        ]);
+
+        let json = serde_json::to_value(&transcript).unwrap();
+        assert_eq!(json, transcript.to_json());
+        assert_eq!(
+            serde_json::from_value::<Transcript>(json).unwrap(),
+            transcript
+        );
+    }
+
+    #[test]
+    fn test_to_srt() {
+        let transcript = transcript(&[
+            recognized("Hello world.", SECOND, SECOND * 2),
+            recognized(
+                "How are you?",
+                SECOND * 3_723 + Ticks::new(4_560_000),
+                SECOND,
+            ),
+        ]);
+
+        assert_eq!(
+            transcript.to_srt(&CaptionOptions::default()),
+            "1\n00:00:01,000 --> 00:00:03,000\nHello world.\n\n\
+             2\n01:02:03,456 --> 01:02:04,456\nHow are you?\n\n"
+        );
+    }
+
+    #[test]
+    fn test_to_webvtt_with_speaker() {
+        let mut transcript = transcript(&[recognized("Hello world.", SECOND, SECOND * 2)]);
+        transcript.phrases[0].speaker_id = Some("Guest-1".to_string());
+
+        assert_eq!(
+            transcript.to_webvtt(&CaptionOptions::default()),
+            "WEBVTT\n\n00:00:01.000 --> 00:00:03.000\n<v Guest-1>Hello world.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_split_channels() {
+        let transcript = transcript(&[
+            recognized("Hello.", SECOND, SECOND).with_channel(0),
+            recognized("Hi.", SECOND * 2, SECOND).with_channel(1),
+        ]);
+
+        assert_eq!(transcript.phrases()[1].channel, Some(1));
+        assert_eq!(transcript.to_json()["phrases"][0]["channel"], 0);
+        assert_eq!(
+            transcript.to_webvtt(&CaptionOptions::default()),
+            "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n<v Channel 0>Hello.\n\n\
+             00:00:02.000 --> 00:00:03.000\n<v Channel 1>Hi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_split_lines_and_captions() {
+        // 40 characters over 4 seconds: 100 ms per character.
+        let transcript = transcript(&[recognized(
+            "aaaa bbbb cccc dddd eeee ffff gggg hhhhh",
+            Ticks::ZERO,
+            SECOND * 4,
+        )]);
+        let options = CaptionOptions::default()
+            .with_max_line_length(10)
+            .with_max_lines(2);
+
+        assert_eq!(
+            transcript.to_srt(&options),
+            "1\n00:00:00,000 --> 00:00:01,900\naaaa bbbb\ncccc dddd\n\n\
+             2\n00:00:01,900 --> 00:00:04,000\neeee ffff\ngggg hhhhh\n\n"
+        );
+    }
+
+    #[test]
+    fn test_split_max_caption_duration() {
+        // 100 ms per character: a caption of 1 second holds 9 characters.
+        let transcript = transcript(&[recognized(
+            "aaaa bbbb cccc dddd",
+            Ticks::ZERO,
+            SECOND * 19 / 10,
+        )]);
+        let options =
+            CaptionOptions::default().with_max_caption_duration(std::time::Duration::from_secs(1));
+
+        let srt = transcript.to_srt(&options);
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:00,900\naaaa bbbb\n\n\
+             2\n00:00:00,900 --> 00:00:01,900\ncccc dddd\n\n"
+        );
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        let transcript = transcript(&[recognized(
+            "Hi. How are you?",
+            Ticks::ZERO,
+            SECOND * 16 / 10,
+        )]);
+        let options = CaptionOptions::default().with_line_split(LineSplit::Sentences);
+
+        assert_eq!(
+            transcript.to_srt(&options),
+            "1\n00:00:00,000 --> 00:00:00,300\nHi.\n\n\
+             2\n00:00:00,300 --> 00:00:01,600\nHow are you?\n\n"
+        );
+    }
+
+    #[test]
+    fn test_split_characters() {
+        let transcript = transcript(&[recognized("你好世界 再见", Ticks::ZERO, SECOND * 6)]);
+        let options = CaptionOptions::default()
+            .with_line_split(LineSplit::Characters)
+            .with_max_line_length(4)
+            .with_max_lines(1);
+
+        assert_eq!(
+            transcript.to_srt(&options),
+            "1\n00:00:00,000 --> 00:00:04,000\n你好世界\n\n\
+             2\n00:00:04,000 --> 00:00:06,000\n再见\n\n"
+        );
+    }
+}
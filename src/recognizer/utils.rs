@@ -1,5 +1,5 @@
 use crate::recognizer::config::Config;
-use crate::recognizer::{AudioDevice, AudioFormat};
+use crate::recognizer::{AudioDevice, AudioFormat, WavSpec};
 use crate::{make_binary_payload, make_text_payload};
 use serde_json::{json, Value};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -9,7 +9,20 @@ pub(crate) fn create_speech_config_message(
     request_id: String,
     config: &Config,
     audio_device: &AudioDevice,
+    spec: Option<&WavSpec>,
 ) -> Message {
+    let mut source = json!({
+        "connectivity": audio_device.connectivity,
+        "manufacturer": audio_device.manufacturer,
+        "model": audio_device.model,
+        "type": audio_device.source,
+    });
+    if let Some(spec) = spec {
+        source["samplerate"] = json!(spec.sample_rate);
+        source["bitspersample"] = json!(spec.bits_per_sample);
+        source["channelcount"] = json!(spec.channels);
+    }
+
     Message::text(make_text_payload(
         vec![
             ("X-RequestId".to_string(), request_id),
@@ -30,15 +43,7 @@ pub(crate) fn create_speech_config_message(
                     "system": config.device.system,
                     "os": config.device.os,
                     "audio": {
-                        "source": {
-                            "connectivity": audio_device.connectivity,
-                            "manufacturer": audio_device.manufacturer,
-                            "model": audio_device.model,
-                            "type": audio_device.name,
-                            //"samplerate": spec.sample_rate,
-                            //"bitspersample": spec.bits_per_sample,
-                            //"channelcount": spec.channels,
-                        }
+                        "source": source,
                     },
                 },
                 "recognition": config.mode,
@@ -48,26 +53,31 @@ pub(crate) fn create_speech_config_message(
     ))
 }
 
-pub(crate) fn create_speech_context_message(request_id: String, config: &Config) -> Message {
-    let mut context = json!({});
+pub(crate) fn create_speech_context_message(
+    request_id: String,
+    config: &Config,
+) -> crate::Result<Message> {
+    Ok(make_speech_context_message(
+        request_id,
+        &create_speech_context(config)?,
+    ))
+}
 
-    if let Some(grammars) = config.phrases.as_ref() {
-        let texts: Vec<Value> = grammars.iter().map(|x| json!({ "Text": x })).collect();
+/// Creates the `speech.context` payload for the given configuration.
+pub(crate) fn create_speech_context(config: &Config) -> crate::Result<Value> {
+    config.validate_custom_models()?;
+    config.validate_result_filter()?;
+    let mut context = json!({});
 
-        context["dgi"] = json!({
-            "Groups": [
-                {
-                    "Type": "Generic",
-                    "Items": texts,
-                }
-            ]
-        });
+    if !config.grammar.is_empty() {
+        config.grammar.validate()?;
+        context["dgi"] = config.grammar.to_json();
     }
 
-    if config.languages.len() > 1 {
+    if config.is_language_detection_enabled() {
         context["languageId"] = json!({
             "mode": config.language_detect_mode.as_ref().unwrap(),
-            "Priority": "PrioritizeLatency",
+            "Priority": config.language_id_priority,
             "languages": config.languages.iter().map(|x| x.to_string()).collect::<Vec<String>>(),
             "onSuccess": {
                 "action": "Recognize"
@@ -99,9 +109,8 @@ pub(crate) fn create_speech_context_message(request_id: String, config: &Config)
             // },
 
             "customModels": custom_models,
-            // todo: when translation, this are set to { action: "Translate" }
+            // The translator sets these to { action: "Translate" }.
             "onInterim": Value::Null,
-            // todo: when translation, this are set to { action: "Translate" }
             "onSuccess": Value::Null,
         });
 
@@ -113,8 +122,27 @@ pub(crate) fn create_speech_context_message(request_id: String, config: &Config)
                 "resultType": "Always"
             }
         });
+
+        if config.language_identification_only {
+            context["languageId"]["onSuccess"] = json!({ "action": "None" });
+        }
+    }
+
+    if let Some(pronunciation_assessment) = config.pronunciation_assessment.as_ref() {
+        context["phraseDetection"]["enrichment"] = json!({
+            "pronunciationAssessment": pronunciation_assessment.to_json(),
+        });
+
+        context["phraseOutput"]["format"] = json!("Detailed");
+        context["phraseOutput"]["detailed"] = json!({
+            "options": ["WordTimings", "PronunciationAssessment", "SNR"],
+        });
     }
 
+    Ok(context)
+}
+
+pub(crate) fn make_speech_context_message(request_id: String, context: &Value) -> Message {
     Message::text(make_text_payload(
         vec![
             ("X-RequestId".to_string(), request_id.to_string()),
@@ -175,3 +203,312 @@ pub(crate) fn create_audio_message(request_id: String, data: Option<&[u8]>) -> M
 
     Message::binary(make_binary_payload(headers, data))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::{
+        ClassEntry, ClassGroup, Grammar, Granularity, Language, LanguageDetectMode,
+        LanguageIdPriority, OutputFormat, PhraseList, PronunciationAssessmentConfig, ResultFilter,
+        SampleFormat, SourceType,
+    };
+    use crate::{Data, Message as EzMessage};
+
+    fn context_of(config: &Config) -> Value {
+        let msg =
+            EzMessage::try_from(create_speech_context_message("id".to_string(), config).unwrap())
+                .unwrap();
+
+        assert_eq!(msg.path, "speech.context");
+        assert_eq!(msg.id, "id");
+
+        match msg.data {
+            Data::Text(Some(ref body)) => serde_json::from_str(body).unwrap(),
+            _ => panic!("expected text body"),
+        }
+    }
+
+    #[test]
+    fn test_create_speech_context_message_default() {
+        assert_eq!(context_of(&Config::default()), json!({}));
+    }
+
+    #[test]
+    fn test_create_speech_context_message_pronunciation_assessment() {
+        let config = Config::default().set_pronunciation_assessment(
+            PronunciationAssessmentConfig::new("Hello world").with_granularity(Granularity::Word),
+        );
+        let context = context_of(&config);
+
+        assert_eq!(
+            context["phraseDetection"]["enrichment"]["pronunciationAssessment"],
+            json!({
+                "referenceText": "Hello world",
+                "gradingSystem": "HundredMark",
+                "granularity": "Word",
+                "dimension": "Comprehensive",
+                "enableMiscue": false,
+            })
+        );
+        assert_eq!(context["phraseOutput"]["format"], "Detailed");
+        assert_eq!(
+            context["phraseOutput"]["detailed"]["options"],
+            json!(["WordTimings", "PronunciationAssessment", "SNR"])
+        );
+    }
+
+    #[test]
+    fn test_create_speech_context_message_phrases() {
+        let config = Config::default().set_phrases(vec!["Contoso".to_string()]);
+
+        assert_eq!(
+            context_of(&config),
+            json!({
+                "dgi": {
+                    "Groups": [
+                        { "Type": "Generic", "Items": [{ "Text": "Contoso" }] },
+                    ],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_create_speech_context_message_grammar() {
+        let config = Config::default().set_grammar(
+            Grammar::new()
+                .with_phrase_list(
+                    PhraseList::new(vec!["Contoso", "Fabrikam"])
+                        .with_name("companies")
+                        .with_weight(1.5)
+                        .with_weighted_phrase("Northwind", 0.5),
+                )
+                .with_class_group(ClassGroup::people(vec![
+                    ClassEntry::new("Jessie Doe")
+                        .with_name_parts(Some("Jessie"), None, Some("Doe"))
+                        .with_synonyms(vec!["JD"])
+                        .with_weight(2.0),
+                    ClassEntry::new("Alex"),
+                ]))
+                .with_reference_grammar("luis/grammar-id"),
+        );
+
+        assert_eq!(
+            context_of(&config),
+            json!({
+                "dgi": {
+                    "ReferenceGrammars": ["luis/grammar-id"],
+                    "Groups": [
+                        {
+                            "Type": "Generic",
+                            "Name": "companies",
+                            "Items": [
+                                { "Text": "Contoso", "Weight": 1.5 },
+                                { "Text": "Fabrikam", "Weight": 1.5 },
+                                { "Text": "Northwind", "Weight": 0.5 },
+                            ],
+                        },
+                        {
+                            "Type": "People",
+                            "Items": [
+                                {
+                                    "Name": "Jessie Doe",
+                                    "First": "Jessie",
+                                    "Last": "Doe",
+                                    "Synonyms": ["JD"],
+                                    "Weight": 2.0,
+                                },
+                                { "Name": "Alex" },
+                            ],
+                        },
+                    ],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_create_speech_context_message_invalid_grammar() {
+        let config = Config::default().set_grammar(
+            Grammar::new().with_phrase_list(PhraseList::new(vec!["Contoso"]).with_weight(3.0)),
+        );
+
+        assert!(create_speech_context_message("id".to_string(), &config).is_err());
+    }
+
+    #[test]
+    fn test_create_speech_context_message_result_filter_requires_detailed_output() {
+        let config = Config::default().set_result_filter(ResultFilter::new());
+        assert!(create_speech_context_message("id".to_string(), &config).is_ok());
+
+        let config = config.set_output_format(OutputFormat::Simple);
+        assert!(matches!(
+            create_speech_context_message("id".to_string(), &config),
+            Err(crate::Error::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_create_speech_context_message_custom_models() {
+        let config = Config::default()
+            .set_detect_languages(
+                vec![Language::EnUs, Language::ItIt],
+                LanguageDetectMode::Continuous,
+            )
+            .set_custom_models(vec![
+                ("en-US".to_string(), "en-endpoint".to_string()),
+                ("it-IT".to_string(), "it-endpoint".to_string()),
+            ]);
+
+        assert_eq!(
+            context_of(&config)["phraseDetection"]["customModels"],
+            json!([
+                { "language": "en-US", "endpoint": "en-endpoint" },
+                { "language": "it-IT", "endpoint": "it-endpoint" },
+            ])
+        );
+        assert_eq!(config.custom_endpoint_id(), None);
+    }
+
+    #[test]
+    fn test_create_speech_context_message_single_language_custom_model() {
+        let config = Config::default()
+            .set_language(Language::ItIt)
+            .set_custom_models(vec![("it-IT".to_string(), "it-endpoint".to_string())]);
+
+        // The model is used as the endpoint of the connection, not in the context.
+        assert_eq!(context_of(&config), json!({}));
+        assert_eq!(config.custom_endpoint_id(), Some("it-endpoint"));
+
+        let config = config.set_endpoint_id("endpoint");
+        assert_eq!(context_of(&config), json!({}));
+        assert_eq!(config.custom_endpoint_id(), Some("endpoint"));
+    }
+
+    #[test]
+    fn test_create_speech_context_message_invalid_custom_models() {
+        let unknown_language = Config::default()
+            .set_language(Language::EnUs)
+            .set_custom_models(vec![("it-IT".to_string(), "it-endpoint".to_string())]);
+        let duplicated_language = Config::default()
+            .set_language(Language::EnUs)
+            .set_custom_models(vec![
+                ("en-US".to_string(), "a".to_string()),
+                ("en-us".to_string(), "b".to_string()),
+            ]);
+        let endpoint_with_languages = Config::default()
+            .set_detect_languages(
+                vec![Language::EnUs, Language::ItIt],
+                LanguageDetectMode::Continuous,
+            )
+            .set_endpoint_id("endpoint");
+        let empty_endpoint = Config::default().set_endpoint_id(" ");
+
+        for config in [
+            unknown_language,
+            duplicated_language,
+            endpoint_with_languages,
+            empty_endpoint,
+        ] {
+            assert!(matches!(
+                create_speech_context_message("id".to_string(), &config),
+                Err(crate::Error::ConfigError(message)) if message.starts_with("Invalid custom model:")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_create_speech_context_message_identify_languages() {
+        let config = Config::default()
+            .set_identify_languages(
+                vec![Language::EnUs, Language::ItIt],
+                LanguageDetectMode::AtStart,
+            )
+            .set_language_id_priority(LanguageIdPriority::Accuracy);
+
+        assert_eq!(
+            context_of(&config)["languageId"],
+            json!({
+                "mode": "DetectAtAudioStart",
+                "Priority": "PrioritizeAccuracy",
+                "languages": ["en-US", "it-IT"],
+                "onSuccess": { "action": "None" },
+                "onUnknown": { "action": "None" },
+            })
+        );
+
+        let config = Config::default().set_detect_languages(
+            vec![Language::EnUs, Language::ItIt],
+            LanguageDetectMode::Continuous,
+        );
+        assert_eq!(
+            context_of(&config)["languageId"]["Priority"],
+            "PrioritizeLatency"
+        );
+        assert_eq!(
+            context_of(&config)["languageId"]["onSuccess"],
+            json!({ "action": "Recognize" })
+        );
+    }
+
+    #[test]
+    fn test_create_speech_context_message_recognize_after_identify_languages() {
+        let config = Config::default()
+            .set_identify_languages(
+                vec![Language::EnUs, Language::ItIt],
+                LanguageDetectMode::AtStart,
+            )
+            .set_detect_languages(
+                vec![Language::EnUs, Language::ItIt],
+                LanguageDetectMode::AtStart,
+            );
+        assert_eq!(
+            context_of(&config)["languageId"]["onSuccess"],
+            json!({ "action": "Recognize" })
+        );
+
+        let config = Config::default()
+            .set_identify_languages(
+                vec![Language::EnUs, Language::ItIt],
+                LanguageDetectMode::AtStart,
+            )
+            .set_language(Language::EnUs);
+        assert!(!config.is_language_detection_enabled());
+        assert_eq!(context_of(&config)["languageId"], Value::Null);
+    }
+
+    #[test]
+    fn test_create_speech_config_message_audio_source() {
+        let spec = WavSpec {
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            channels: 1,
+            sample_format: SampleFormat::Int,
+        };
+        let msg = EzMessage::try_from(create_speech_config_message(
+            "id".to_string(),
+            &Config::default(),
+            &AudioDevice::new(SourceType::Microphones).with_name("Default"),
+            Some(&spec),
+        ))
+        .unwrap();
+
+        assert_eq!(msg.path, "speech.config");
+        let body: Value = match msg.data {
+            Data::Text(Some(ref body)) => serde_json::from_str(body).unwrap(),
+            _ => panic!("expected text body"),
+        };
+        assert_eq!(
+            body["context"]["audio"]["source"],
+            json!({
+                "connectivity": "Unknown",
+                "manufacturer": "",
+                "model": "",
+                "type": "Microphones",
+                "samplerate": 16000,
+                "bitspersample": 16,
+                "channelcount": 1,
+            })
+        );
+    }
+}
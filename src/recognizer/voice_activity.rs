@@ -0,0 +1,362 @@
+use crate::recognizer::audio_converter::down_mix;
+use crate::recognizer::{Duration, Offset, SampleFormat, WavSpec};
+use std::collections::VecDeque;
+
+/// The length of the analysed frames, in milliseconds.
+const FRAME_MS: u32 = 20;
+
+/// The configuration of the client-side voice activity detection.
+///
+/// The audio is split in frames of 20 ms, and each frame is classified as speech or silence
+/// from its energy and zero-crossing rate. The silence longer than the pre-roll and post-roll
+/// is not sent to the service, up to the maximum withheld span. Only PCM audio is analysed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoiceActivityDetection {
+    pub(crate) energy_threshold: f64,
+    pub(crate) zero_crossing_threshold: f64,
+    pub(crate) pre_roll: std::time::Duration,
+    pub(crate) post_roll: std::time::Duration,
+    pub(crate) max_withheld: std::time::Duration,
+}
+
+impl Default for VoiceActivityDetection {
+    fn default() -> Self {
+        Self {
+            energy_threshold: 0.01,
+            zero_crossing_threshold: 0.3,
+            pre_roll: std::time::Duration::from_millis(300),
+            post_roll: std::time::Duration::from_millis(800),
+            max_withheld: std::time::Duration::from_secs(10),
+        }
+    }
+}
+
+impl VoiceActivityDetection {
+    /// Create the configuration with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the RMS energy, in the `[0.0, 1.0]` range, above which a frame is speech.
+    ///
+    /// Frames above half of this energy are speech too, when their zero-crossing rate is high,
+    /// to keep the unvoiced consonants. Default: `0.01`.
+    pub fn with_energy_threshold(mut self, energy_threshold: f64) -> Self {
+        self.energy_threshold = energy_threshold;
+        self
+    }
+
+    /// Set the zero-crossing rate, in the `[0.0, 1.0]` range, above which a low energy frame is speech.
+    ///
+    /// Default: `0.3`.
+    pub fn with_zero_crossing_threshold(mut self, zero_crossing_threshold: f64) -> Self {
+        self.zero_crossing_threshold = zero_crossing_threshold;
+        self
+    }
+
+    /// Set the silence sent before the speech. Default: 300 ms.
+    pub fn with_pre_roll(mut self, pre_roll: std::time::Duration) -> Self {
+        self.pre_roll = pre_roll;
+        self
+    }
+
+    /// Set the silence sent after the speech. Default: 800 ms.
+    ///
+    /// The service needs some silence to detect the end of the phrases.
+    pub fn with_post_roll(mut self, post_roll: std::time::Duration) -> Self {
+        self.post_roll = post_roll;
+        self
+    }
+
+    /// Set the longest silence withheld at once. Default: 10 s.
+    ///
+    /// The service does not send anything while it receives no audio, and the connection times
+    /// out after 30 s without messages: after this span, the silence is sent as the speech is,
+    /// with its pre-roll and post-roll.
+    pub fn with_max_withheld(mut self, max_withheld: std::time::Duration) -> Self {
+        self.max_withheld = max_withheld;
+        self
+    }
+}
+
+/// Withholds the silence of a PCM stream.
+#[derive(Debug)]
+pub(crate) struct VoiceActivityDetector {
+    config: VoiceActivityDetection,
+    spec: WavSpec,
+    frame_size: usize,
+    /// Bytes of an incomplete frame, waiting for the next chunk.
+    pending: Vec<u8>,
+    /// The last silence frames, sent before the next speech.
+    pre_roll: VecDeque<Vec<u8>>,
+    pre_roll_frames: usize,
+    post_roll_frames: usize,
+    /// The bytes withheld at once, after which the silence is sent.
+    max_withheld: u64,
+    /// The silence frames still to send after the last speech.
+    post_roll_remaining: usize,
+    /// The bytes sent and withheld so far.
+    emitted: u64,
+    skipped: u64,
+}
+
+impl VoiceActivityDetector {
+    /// Create the detector for the given spec.
+    ///
+    /// Returns `None` if the audio cannot be analysed.
+    pub(crate) fn new(config: VoiceActivityDetection, spec: WavSpec) -> Option<Self> {
+        if !matches!(spec.sample_format, SampleFormat::Int | SampleFormat::Float) {
+            return None;
+        }
+
+        let frames = |duration: std::time::Duration| {
+            ((duration.as_millis() as u32 + FRAME_MS - 1) / FRAME_MS) as usize
+        };
+        let samples_per_frame = (spec.sample_rate * FRAME_MS / 1000).max(1) as usize;
+
+        let frame_size = samples_per_frame * spec.block_align() as usize;
+
+        Some(Self {
+            frame_size,
+            pre_roll_frames: frames(config.pre_roll),
+            post_roll_frames: frames(config.post_roll),
+            max_withheld: (frames(config.max_withheld) * frame_size) as u64,
+            config,
+            spec,
+            pending: vec![],
+            pre_roll: VecDeque::new(),
+            post_roll_remaining: 0,
+            emitted: 0,
+            skipped: 0,
+        })
+    }
+
+    /// Process the next chunk of audio.
+    ///
+    /// Returns the audio to send, and the silences withheld before it, as the offset
+    /// in the sent audio where the silence was removed, and the duration of the silence.
+    pub(crate) fn process(&mut self, data: &[u8]) -> (Vec<u8>, Vec<(Offset, Duration)>) {
+        self.pending.extend_from_slice(data);
+
+        let mut output = vec![];
+        let mut gaps = vec![];
+        while self.pending.len() >= self.frame_size {
+            let frame: Vec<u8> = self.pending.drain(..self.frame_size).collect();
+
+            // After the longest withheld span, the silence keeps the connection alive.
+            if self.is_speech(&frame) || self.skipped >= self.max_withheld {
+                if self.skipped > 0 {
+                    gaps.push((
+                        self.spec.offset_of(self.emitted),
+                        self.spec.offset_of(self.skipped),
+                    ));
+                    self.skipped = 0;
+                }
+                for frame in self.pre_roll.drain(..) {
+                    self.emitted += frame.len() as u64;
+                    output.extend(frame);
+                }
+                self.emitted += frame.len() as u64;
+                output.extend(frame);
+                self.post_roll_remaining = self.post_roll_frames;
+            } else if self.post_roll_remaining > 0 {
+                self.post_roll_remaining -= 1;
+                self.emitted += frame.len() as u64;
+                output.extend(frame);
+            } else {
+                self.pre_roll.push_back(frame);
+                if self.pre_roll.len() > self.pre_roll_frames {
+                    if let Some(frame) = self.pre_roll.pop_front() {
+                        self.skipped += frame.len() as u64;
+                    }
+                }
+            }
+        }
+
+        (output, gaps)
+    }
+
+    /// Process the end of the audio.
+    ///
+    /// The silence kept for the pre-roll and the incomplete frame are sent, as the end of the
+    /// audio may be needed by the service to finish the last phrase.
+    pub(crate) fn flush(&mut self) -> (Vec<u8>, Vec<(Offset, Duration)>) {
+        let mut output = vec![];
+        let mut gaps = vec![];
+        let pending = std::mem::take(&mut self.pending);
+        if self.pre_roll.is_empty() && pending.is_empty() {
+            return (output, gaps);
+        }
+
+        if self.skipped > 0 {
+            gaps.push((
+                self.spec.offset_of(self.emitted),
+                self.spec.offset_of(self.skipped),
+            ));
+            self.skipped = 0;
+        }
+        for frame in self.pre_roll.drain(..).chain(std::iter::once(pending)) {
+            self.emitted += frame.len() as u64;
+            output.extend(frame);
+        }
+
+        (output, gaps)
+    }
+
+    fn is_speech(&self, frame: &[u8]) -> bool {
+        let samples: Vec<f64> = frame
+            .chunks_exact(self.spec.block_align() as usize)
+            .map(|frame| down_mix(frame, &self.spec))
+            .collect();
+
+        let energy = (samples.iter().map(|s| s * s).sum::<f64>() / samples.len() as f64).sqrt();
+        let crossings = samples
+            .windows(2)
+            .filter(|w| (w[0] < 0.0) != (w[1] < 0.0))
+            .count();
+        let zero_crossing_rate = crossings as f64 / samples.len().saturating_sub(1).max(1) as f64;
+
+        energy >= self.config.energy_threshold
+            || (energy >= self.config.energy_threshold / 2.0
+                && zero_crossing_rate >= self.config.zero_crossing_threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::audio_converter::TARGET_SPEC;
+    use crate::Ticks;
+    use std::f64::consts::PI;
+
+    /// 20 ms at 16 kHz 16-bit.
+    const FRAME: usize = 640;
+    const FRAME_TICKS: Ticks = Ticks::from_millis(20);
+
+    fn silence(frames: usize) -> Vec<u8> {
+        vec![0; frames * FRAME]
+    }
+
+    fn tone(frames: usize) -> Vec<u8> {
+        (0..frames * FRAME / 2)
+            .flat_map(|i| {
+                let s = 0.3 * (2.0 * PI * 440.0 * i as f64 / 16000.0).sin();
+                ((s * 32767.0) as i16).to_le_bytes()
+            })
+            .collect()
+    }
+
+    fn hiss(frames: usize) -> Vec<u8> {
+        // Alternating samples: a high zero-crossing rate, with an energy of 0.006.
+        (0..frames * FRAME / 2)
+            .flat_map(|i| (if i % 2 == 0 { 200i16 } else { -200i16 }).to_le_bytes())
+            .collect()
+    }
+
+    fn detector() -> VoiceActivityDetector {
+        VoiceActivityDetector::new(
+            VoiceActivityDetection::new()
+                .with_pre_roll(std::time::Duration::from_millis(100))
+                .with_post_roll(std::time::Duration::from_millis(200)),
+            TARGET_SPEC,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_withholds_long_silence_with_padding() {
+        let mut vad = detector();
+        let audio = [silence(50), tone(10), silence(100), tone(10), silence(50)].concat();
+
+        let mut output = vec![];
+        let mut gaps = vec![];
+        // Uneven chunks, to exercise the partial frames.
+        for chunk in audio.chunks(1000) {
+            let (data, skipped) = vad.process(chunk);
+            output.extend(data);
+            gaps.extend(skipped);
+        }
+
+        // pre-roll (5 frames) + tone (10) + post-roll (10), twice.
+        assert_eq!(output.len(), 2 * 25 * FRAME);
+        assert_eq!(&output[5 * FRAME..15 * FRAME], &tone(10)[..]);
+        assert_eq!(
+            gaps,
+            vec![
+                (Ticks::ZERO, FRAME_TICKS * 45),
+                (FRAME_TICKS * 25, FRAME_TICKS * 85)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flush_sends_the_trailing_audio() {
+        let mut vad = detector();
+        let (output, _) = vad.process(&[tone(10), silence(30)].concat());
+        // tone (10) + post-roll (10), and 5 frames of pre-roll withheld.
+        assert_eq!(output.len(), 20 * FRAME);
+
+        let (output, _) = vad.process(&silence(1)[..100]);
+        assert!(output.is_empty());
+
+        let (output, gaps) = vad.flush();
+        assert_eq!(output.len(), 5 * FRAME + 100);
+        assert_eq!(gaps, vec![(FRAME_TICKS * 20, FRAME_TICKS * 15)]);
+        assert_eq!(vad.flush(), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_sends_the_silence_after_the_max_withheld_span() {
+        let mut vad = VoiceActivityDetector::new(
+            VoiceActivityDetection::new()
+                .with_pre_roll(std::time::Duration::from_millis(100))
+                .with_post_roll(std::time::Duration::from_millis(200))
+                .with_max_withheld(std::time::Duration::from_secs(1)),
+            TARGET_SPEC,
+        )
+        .unwrap();
+
+        let (output, gaps) = vad.process(&silence(120));
+
+        // After 1 s withheld: pre-roll (5 frames) + frame (1) + post-roll (10).
+        assert_eq!(output.len(), 16 * FRAME);
+        assert_eq!(gaps, vec![(Ticks::ZERO, FRAME_TICKS * 50)]);
+    }
+
+    #[test]
+    fn test_keeps_short_silence() {
+        let mut vad = detector();
+        let audio = [tone(10), silence(12), tone(10)].concat();
+
+        let (output, gaps) = vad.process(&audio);
+
+        assert_eq!(output, audio);
+        assert!(gaps.is_empty());
+    }
+
+    #[test]
+    fn test_low_energy_high_zero_crossing_is_speech() {
+        let mut vad = detector();
+
+        let (output, _) = vad.process(&[silence(20), hiss(10)].concat());
+        assert_eq!(output.len(), 15 * FRAME);
+
+        let mut vad = VoiceActivityDetector::new(
+            VoiceActivityDetection::new().with_zero_crossing_threshold(1.1),
+            TARGET_SPEC,
+        )
+        .unwrap();
+        let (output, _) = vad.process(&[silence(50), hiss(10)].concat());
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_compressed_audio_is_not_analysed() {
+        let spec = WavSpec {
+            sample_format: SampleFormat::MuLaw,
+            bits_per_sample: 8,
+            ..TARGET_SPEC
+        };
+        assert!(VoiceActivityDetector::new(VoiceActivityDetection::new(), spec).is_none());
+    }
+}
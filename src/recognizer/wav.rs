@@ -0,0 +1,461 @@
+use crate::recognizer::Offset;
+
+/// `WAVE_FORMAT_PCM`
+const FORMAT_PCM: u16 = 0x0001;
+/// `WAVE_FORMAT_IEEE_FLOAT`
+const FORMAT_IEEE_FLOAT: u16 = 0x0003;
+/// `WAVE_FORMAT_ALAW`
+const FORMAT_ALAW: u16 = 0x0006;
+/// `WAVE_FORMAT_MULAW`
+const FORMAT_MULAW: u16 = 0x0007;
+/// `WAVE_FORMAT_EXTENSIBLE`
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// The tail shared by all the `KSDATAFORMAT_SUBTYPE_*` GUIDs, after the format tag.
+const SUBFORMAT_GUID_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+/// The encoding of the samples in a WAV stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SampleFormat {
+    /// Signed integer PCM (unsigned for 8 bits).
+    Int,
+    /// IEEE floating point PCM.
+    Float,
+    /// G.711 A-law.
+    ALaw,
+    /// G.711 µ-law.
+    MuLaw,
+}
+
+/// The specification of a WAV audio stream, read from its `fmt ` chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WavSpec {
+    /// The number of samples per second.
+    pub sample_rate: u32,
+    /// The number of bits of each sample.
+    pub bits_per_sample: u16,
+    /// The number of interleaved channels.
+    pub channels: u16,
+    /// The encoding of the samples.
+    pub sample_format: SampleFormat,
+}
+
+impl WavSpec {
+    /// The number of bytes of a frame: one sample for each channel.
+    ///
+    /// Saturates at `u16::MAX` when the frame does not fit in the `fmt ` chunk field.
+    pub fn block_align(&self) -> u16 {
+        self.channels.saturating_mul(self.sample_size())
+    }
+
+    /// The number of bytes per second of audio.
+    ///
+    /// Saturates at `u32::MAX` when the byte rate does not fit in the `fmt ` chunk field.
+    pub fn byte_rate(&self) -> u32 {
+        self.sample_rate.saturating_mul(self.block_align() as u32)
+    }
+
+    /// The number of bytes of a sample, rounded up to a whole byte.
+    fn sample_size(&self) -> u16 {
+        self.bits_per_sample / 8 + u16::from(self.bits_per_sample % 8 != 0)
+    }
+
+    /// The `RIFF` header of a stream with this spec, up to the beginning of the `data` chunk.
+    ///
+    /// The sizes of the `RIFF` and `data` chunks are unknown, and set to 0.
+    pub(crate) fn to_header(self) -> Vec<u8> {
+        let format_tag = match self.sample_format {
+            SampleFormat::Int => FORMAT_PCM,
+            SampleFormat::Float => FORMAT_IEEE_FLOAT,
+            SampleFormat::ALaw => FORMAT_ALAW,
+            SampleFormat::MuLaw => FORMAT_MULAW,
+        };
+
+        let mut header = Vec::with_capacity(44);
+        header.extend(b"RIFF");
+        header.extend(0u32.to_le_bytes());
+        header.extend(b"WAVE");
+        header.extend(b"fmt ");
+        header.extend(16u32.to_le_bytes());
+        header.extend(format_tag.to_le_bytes());
+        header.extend(self.channels.to_le_bytes());
+        header.extend(self.sample_rate.to_le_bytes());
+        header.extend(self.byte_rate().to_le_bytes());
+        header.extend(self.block_align().to_le_bytes());
+        header.extend(self.bits_per_sample.to_le_bytes());
+        header.extend(b"data");
+        header.extend(0u32.to_le_bytes());
+        header
+    }
+
+    /// The offset, in ticks of 100 nanoseconds, at the end of the given amount of audio bytes.
+    ///
+    /// The offset is always 0 when the byte rate is 0.
+    pub fn offset_of(&self, bytes: u64) -> Offset {
+        match self.byte_rate() {
+            0 => Offset::ZERO,
+            byte_rate => Offset::new((bytes as u128 * 10_000_000 / byte_rate as u128) as u64),
+        }
+    }
+
+    /// The amount of audio bytes before the given offset, rounded down to a whole frame.
+    pub(crate) fn bytes_at(&self, offset: Offset) -> u64 {
+        let bytes = (offset.as_ticks() as u128 * self.byte_rate() as u128 / 10_000_000) as u64;
+        bytes - bytes.checked_rem(self.block_align() as u64).unwrap_or(0)
+    }
+
+    /// Check that the spec is supported.
+    pub(crate) fn validate(&self) -> crate::Result<()> {
+        match (self.sample_format, self.bits_per_sample) {
+            (SampleFormat::Int, 8 | 16 | 24 | 32)
+            | (SampleFormat::Float, 32 | 64)
+            | (SampleFormat::ALaw | SampleFormat::MuLaw, 8) => {}
+            (sample_format, bits) => {
+                return Err(invalid(&format!(
+                    "{bits} bits per sample are not supported for the {sample_format:?} format"
+                )))
+            }
+        }
+
+        if self.channels == 0 {
+            return Err(invalid("no channels"));
+        }
+        if self.sample_rate == 0 {
+            return Err(invalid("the sample rate is 0"));
+        }
+
+        // The frame size and the byte rate must fit in the fields of the header.
+        let block_align = self
+            .channels
+            .checked_mul(self.sample_size())
+            .ok_or_else(|| invalid("the block align overflows"))?;
+        self.sample_rate
+            .checked_mul(block_align as u32)
+            .ok_or_else(|| invalid("the byte rate overflows"))?;
+
+        Ok(())
+    }
+
+    fn from_fmt_chunk(chunk: &[u8]) -> crate::Result<Self> {
+        if chunk.len() < 16 {
+            return Err(invalid("the fmt chunk is too short"));
+        }
+
+        let mut format_tag = read_u16(chunk, 0);
+        let channels = read_u16(chunk, 2);
+        let sample_rate = read_u32(chunk, 4);
+        let block_align = read_u16(chunk, 12);
+        let bits_per_sample = read_u16(chunk, 14);
+
+        if format_tag == FORMAT_EXTENSIBLE {
+            if chunk.len() < 40 || read_u16(chunk, 16) < 22 {
+                return Err(invalid("the WAVE_FORMAT_EXTENSIBLE fmt chunk is too short"));
+            }
+            let valid_bits = read_u16(chunk, 18);
+            if valid_bits != 0 && valid_bits != bits_per_sample {
+                return Err(invalid(&format!(
+                    "{valid_bits} valid bits in {bits_per_sample} bits containers are not supported"
+                )));
+            }
+            if chunk[26..40] != SUBFORMAT_GUID_TAIL {
+                return Err(invalid("unknown WAVE_FORMAT_EXTENSIBLE sub format"));
+            }
+            format_tag = read_u16(chunk, 24);
+        }
+
+        let sample_format = match format_tag {
+            FORMAT_PCM => SampleFormat::Int,
+            FORMAT_IEEE_FLOAT => SampleFormat::Float,
+            FORMAT_ALAW => SampleFormat::ALaw,
+            FORMAT_MULAW => SampleFormat::MuLaw,
+            tag => return Err(invalid(&format!("unsupported format {tag:#06x}"))),
+        };
+
+        let spec = Self {
+            sample_rate,
+            bits_per_sample,
+            channels,
+            sample_format,
+        };
+        spec.validate()?;
+        if block_align != spec.block_align() {
+            return Err(invalid(&format!(
+                "the block align is {block_align}, expected {}",
+                spec.block_align()
+            )));
+        }
+
+        Ok(spec)
+    }
+}
+
+/// Parse the header of a WAV stream, up to the beginning of the `data` chunk.
+///
+/// Returns `None` when more bytes are needed, otherwise the spec and the length of the header.
+pub(crate) fn parse_wav_header(bytes: &[u8]) -> crate::Result<Option<(WavSpec, usize)>> {
+    if bytes.len() < 12 {
+        return Ok(None);
+    }
+    if &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(invalid("missing the RIFF/WAVE identifiers"));
+    }
+
+    let mut spec = None;
+    let mut position = 12;
+    loop {
+        if bytes.len() < position + 8 {
+            return Ok(None);
+        }
+
+        let id = &bytes[position..position + 4];
+        let size = read_u32(bytes, position + 4) as usize;
+        let body = position + 8;
+
+        match id {
+            b"fmt " => {
+                if bytes.len() < body + size {
+                    return Ok(None);
+                }
+                spec = Some(WavSpec::from_fmt_chunk(&bytes[body..body + size])?);
+            }
+            b"data" => {
+                return match spec {
+                    Some(spec) => Ok(Some((spec, body))),
+                    None => Err(invalid("the data chunk is before the fmt chunk")),
+                }
+            }
+            _ => {}
+        }
+
+        // Chunks are aligned on 2 bytes.
+        position = body + size + (size & 1);
+    }
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+fn invalid(reason: &str) -> crate::Error {
+    crate::Error::ParseError(format!("Invalid wav header: {reason}."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ticks;
+
+    fn fmt_chunk(format_tag: u16, channels: u16, sample_rate: u32, bits: u16) -> Vec<u8> {
+        let block_align = channels.wrapping_mul(bits / 8);
+        let mut chunk = vec![];
+        chunk.extend(format_tag.to_le_bytes());
+        chunk.extend(channels.to_le_bytes());
+        chunk.extend(sample_rate.to_le_bytes());
+        chunk.extend(sample_rate.wrapping_mul(block_align as u32).to_le_bytes());
+        chunk.extend(block_align.to_le_bytes());
+        chunk.extend(bits.to_le_bytes());
+        chunk
+    }
+
+    fn extensible_chunk(sub_format: u16, channels: u16, sample_rate: u32, bits: u16) -> Vec<u8> {
+        let mut chunk = fmt_chunk(FORMAT_EXTENSIBLE, channels, sample_rate, bits);
+        chunk.extend(22u16.to_le_bytes());
+        chunk.extend(bits.to_le_bytes());
+        chunk.extend(0x3u32.to_le_bytes());
+        chunk.extend(sub_format.to_le_bytes());
+        chunk.extend(SUBFORMAT_GUID_TAIL);
+        chunk
+    }
+
+    fn wav(chunks: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = b"RIFF\0\0\0\0WAVE".to_vec();
+        for (id, body) in chunks {
+            bytes.extend(*id);
+            bytes.extend((body.len() as u32).to_le_bytes());
+            bytes.extend(body);
+            if body.len() % 2 == 1 {
+                bytes.push(0);
+            }
+        }
+        bytes
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_spec_serde_round_trip() {
+        let spec = WavSpec {
+            sample_rate: 8000,
+            bits_per_sample: 8,
+            channels: 2,
+            sample_format: SampleFormat::MuLaw,
+        };
+
+        let json = serde_json::to_string(&spec).unwrap();
+        assert_eq!(serde_json::from_str::<WavSpec>(&json).unwrap(), spec);
+    }
+
+    #[test]
+    fn test_parse_pcm_header() {
+        let bytes = wav(&[
+            (b"fmt ", fmt_chunk(FORMAT_PCM, 1, 16000, 16)),
+            (b"data", vec![]),
+        ]);
+
+        let (spec, len) = parse_wav_header(&bytes).unwrap().unwrap();
+        assert_eq!(len, 44);
+        assert_eq!(
+            spec,
+            WavSpec {
+                sample_rate: 16000,
+                bits_per_sample: 16,
+                channels: 1,
+                sample_format: SampleFormat::Int,
+            }
+        );
+        assert_eq!(spec.byte_rate(), 32000);
+    }
+
+    #[test]
+    fn test_parse_skips_other_chunks() {
+        // The "data" literal inside the LIST chunk must not be taken as the data chunk.
+        let bytes = wav(&[
+            (b"LIST", b"INFOdata\x05".to_vec()),
+            (b"fmt ", fmt_chunk(FORMAT_IEEE_FLOAT, 2, 48000, 32)),
+            (b"data", vec![]),
+        ]);
+
+        let (spec, len) = parse_wav_header(&bytes).unwrap().unwrap();
+        assert_eq!(len, bytes.len());
+        assert_eq!(spec.channels, 2);
+        assert_eq!(spec.sample_format, SampleFormat::Float);
+    }
+
+    #[test]
+    fn test_parse_extensible_header() {
+        let bytes = wav(&[
+            (b"fmt ", extensible_chunk(FORMAT_PCM, 2, 44100, 24)),
+            (b"data", vec![]),
+        ]);
+
+        let (spec, _) = parse_wav_header(&bytes).unwrap().unwrap();
+        assert_eq!(spec.bits_per_sample, 24);
+        assert_eq!(spec.sample_format, SampleFormat::Int);
+        assert_eq!(spec.block_align(), 6);
+    }
+
+    #[test]
+    fn test_parse_needs_more_bytes() {
+        let bytes = wav(&[
+            (b"fmt ", fmt_chunk(FORMAT_PCM, 1, 16000, 16)),
+            (b"data", vec![]),
+        ]);
+
+        for len in [0, 11, 20, 30, 43] {
+            assert_eq!(parse_wav_header(&bytes[..len]).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_unsupported_layouts() {
+        let cases = [
+            b"RIFX\0\0\0\0WAVE".to_vec(),
+            wav(&[(b"data", vec![])]),
+            wav(&[(b"fmt ", fmt_chunk(0x0002, 1, 16000, 4)), (b"data", vec![])]),
+            wav(&[
+                (b"fmt ", fmt_chunk(FORMAT_PCM, 1, 16000, 12)),
+                (b"data", vec![]),
+            ]),
+            wav(&[
+                (b"fmt ", fmt_chunk(FORMAT_PCM, 0, 16000, 16)),
+                (b"data", vec![]),
+            ]),
+            wav(&[
+                (b"fmt ", fmt_chunk(FORMAT_PCM, 1, 16000, 16)[..14].to_vec()),
+                (b"data", vec![]),
+            ]),
+            wav(&[
+                (b"fmt ", extensible_chunk(0x0002, 1, 16000, 16)),
+                (b"data", vec![]),
+            ]),
+            wav(&[
+                (b"fmt ", fmt_chunk(FORMAT_IEEE_FLOAT, 8192, 16000, 64)),
+                (b"data", vec![]),
+            ]),
+            wav(&[
+                (b"fmt ", fmt_chunk(FORMAT_PCM, 2, 1 << 30, 16)),
+                (b"data", vec![]),
+            ]),
+        ];
+
+        for bytes in cases {
+            assert!(
+                matches!(parse_wav_header(&bytes), Err(crate::Error::ParseError(_))),
+                "{bytes:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_header_from_spec() {
+        let spec = WavSpec {
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            channels: 2,
+            sample_format: SampleFormat::Int,
+        };
+
+        let header = spec.to_header();
+        assert_eq!(header.len(), 44);
+        assert_eq!(parse_wav_header(&header).unwrap(), Some((spec, 44)));
+    }
+
+    #[test]
+    fn test_offset_of() {
+        let spec = WavSpec {
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            channels: 1,
+            sample_format: SampleFormat::Int,
+        };
+
+        assert_eq!(spec.offset_of(0), Ticks::new(0));
+        assert_eq!(spec.offset_of(32000), Ticks::new(10_000_000));
+        assert_eq!(spec.offset_of(320), Ticks::new(100_000));
+
+        assert_eq!(spec.bytes_at(Ticks::new(10_000_000)), 32000);
+        // Rounded down to a whole frame.
+        assert_eq!(spec.bytes_at(Ticks::new(100_400)), 320);
+        assert_eq!(spec.bytes_at(Ticks::new(100_700)), 322);
+    }
+
+    #[test]
+    fn test_helpers_do_not_panic_on_invalid_specs() {
+        let spec = WavSpec {
+            sample_rate: 0,
+            bits_per_sample: 16,
+            channels: 0,
+            sample_format: SampleFormat::Int,
+        };
+        assert_eq!(spec.byte_rate(), 0);
+        assert_eq!(spec.offset_of(32000), Ticks::ZERO);
+        assert_eq!(spec.bytes_at(Ticks::new(10_000_000)), 0);
+
+        let spec = WavSpec {
+            sample_rate: u32::MAX,
+            bits_per_sample: u16::MAX,
+            channels: u16::MAX,
+            sample_format: SampleFormat::Int,
+        };
+        assert_eq!(spec.block_align(), u16::MAX);
+        assert_eq!(spec.byte_rate(), u32::MAX);
+        assert!(spec.validate().is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::callback::Callback;
+use crate::recognizer::{Event, RecognizingDeltas};
 use core::fmt;
 use core::pin::Pin;
 use core::task::{Context, Poll};
@@ -117,6 +118,17 @@ where
         StopAfter::new(self, f)
     }
 
+    /// Replaces the `Recognizing` events of a recognizer stream with `RecognizingDelta` events.
+    ///
+    /// Each delta only holds the change of the hypothesis since the previous one of the same phrase.
+    /// The channels of a split stream have their own hypotheses. The other events are forwarded unchanged.
+    fn recognizing_deltas(self) -> RecognizingDeltas<Self>
+    where
+        Self: Stream<Item = crate::Result<Event>> + Sized,
+    {
+        RecognizingDeltas::new(self)
+    }
+
     /// Calls the provided callback for each item in the stream.
     fn use_callbacks<C>(self, callback: C) -> impl Future<Output = ()>
     where
@@ -5,14 +5,66 @@ use crate::synthesizer::message;
 use crate::RequestId;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Event for the speech recognition
+///
+/// With the `serde` feature, the audio is serialized as a base64 string.
 pub enum Event {
     SessionStarted(RequestId),
     SessionEnded(RequestId),
 
     AudioMetadata(RequestId, Vec<message::Metadata>),
     /// Raw Audio Chunk from the synthesizer.
-    Synthesising(RequestId, Vec<u8>),
+    Synthesising(
+        RequestId,
+        #[cfg_attr(feature = "serde", serde(with = "crate::utils::base64_audio"))] Vec<u8>,
+    ),
     /// Synthesizing has finished.
     Synthesised(RequestId),
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::Ticks;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_audio_is_base64_encoded() {
+        let event = Event::Synthesising(Uuid::nil(), vec![1, 2, 3, 255]);
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["Synthesising"][1], "AQID/w==");
+        assert_eq!(serde_json::from_value::<Event>(json).unwrap(), event);
+    }
+
+    #[test]
+    fn test_events_round_trip() {
+        let events = vec![
+            Event::SessionStarted(Uuid::new_v4()),
+            Event::AudioMetadata(
+                Uuid::nil(),
+                vec![
+                    message::Metadata::WordBoundary {
+                        offset: Ticks::new(500_000),
+                        duration: Ticks::new(3_000_000),
+                        text: message::Text {
+                            text: "hello".to_string(),
+                            length: 5,
+                            boundary_type: message::BoundaryType::Word,
+                        },
+                    },
+                    message::Metadata::SessionEnd {
+                        offset: Ticks::new(16_500_000),
+                    },
+                ],
+            ),
+            Event::Synthesised(Uuid::nil()),
+        ];
+
+        for event in events {
+            let json = serde_json::to_string(&event).unwrap();
+            assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+        }
+    }
+}
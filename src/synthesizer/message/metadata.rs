@@ -1,6 +1,8 @@
+use crate::Ticks;
 use serde::Deserialize;
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum BoundaryType {
     #[serde(rename = "SentenceBoundary")]
     Sentence,
@@ -11,6 +13,7 @@ pub enum BoundaryType {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct Text {
     pub text: String,
@@ -19,30 +22,32 @@ pub struct Text {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[serde(tag = "Type", content = "Data")]
 pub enum Metadata {
     WordBoundary {
         /// Specifies the audio offset, in ticks (100 nanoseconds).
         #[serde(rename = "Offset")]
-        offset: i64,
+        offset: Ticks,
         /// Specifies the duration, in ticks (100 nanoseconds).
         #[serde(rename = "Duration")]
-        duration: i64,
+        duration: Ticks,
         #[serde(rename = "text")]
         text: Text,
     },
     SentenceBoundary {
         /// Specifies the audio offset, in ticks (100 nanoseconds).
         #[serde(rename = "Offset")]
-        offset: i64,
+        offset: Ticks,
         /// Specifies the duration, in ticks (100 nanoseconds).
         #[serde(rename = "Duration")]
-        duration: i64,
+        duration: Ticks,
         text: Text,
     },
     Viseme {
+        /// Specifies the audio offset, in ticks (100 nanoseconds).
         #[serde(rename = "Offset")]
-        offset: i64,
+        offset: Ticks,
         #[serde(rename = "VisemeId")]
         viseme_id: i64,
         #[serde(rename = "IsLastAnimation")]
@@ -51,7 +56,7 @@ pub enum Metadata {
     SessionEnd {
         /// Specifies the audio offset, in ticks (100 nanoseconds).
         #[serde(rename = "Offset")]
-        offset: i64,
+        offset: Ticks,
     },
 }
 
@@ -83,7 +88,7 @@ mod test {
         assert_eq!(root.metadata.len(), 1);
         match &root.metadata[0] {
             Metadata::SessionEnd { offset } => {
-                assert_eq!(*offset, 16500000);
+                assert_eq!(*offset, Ticks::new(16500000));
             }
             _ => panic!("Expected SessionEnd"),
         }
@@ -113,9 +118,9 @@ mod test {
                 viseme_id,
                 is_last_animation,
             } => {
-                assert_eq!(*offset, 500000);
+                assert_eq!(*offset, Ticks::new(500000));
                 assert_eq!(*viseme_id, 0);
-                assert_eq!(*is_last_animation, true);
+                assert!(*is_last_animation);
             }
             _ => panic!("Expected Viseme"),
         }
@@ -149,8 +154,8 @@ mod test {
                 duration,
                 text,
             } => {
-                assert_eq!(*offset, 500000);
-                assert_eq!(*duration, 16000000);
+                assert_eq!(*offset, Ticks::new(500000));
+                assert_eq!(*duration, Ticks::new(16000000));
                 assert_eq!(text.text, "Hello World!");
                 assert_eq!(text.length, 12);
                 assert_eq!(text.boundary_type, BoundaryType::Sentence);
@@ -186,8 +191,8 @@ mod test {
                 duration,
                 text,
             } => {
-                assert_eq!(*offset, 500000);
-                assert_eq!(*duration, 5125000);
+                assert_eq!(*offset, Ticks::new(500000));
+                assert_eq!(*duration, Ticks::new(5125000));
                 assert_eq!(text.text, "Hello");
                 assert_eq!(text.length, 5);
                 assert_eq!(text.boundary_type, BoundaryType::Word);
@@ -225,8 +230,8 @@ mod test {
                 duration,
                 text,
             } => {
-                assert_eq!(*offset, 8500000);
-                assert_eq!(*duration, 1000000);
+                assert_eq!(*offset, Ticks::new(8500000));
+                assert_eq!(*duration, Ticks::new(1000000));
                 assert_eq!(text.text, "!");
                 assert_eq!(text.length, 1);
                 assert_eq!(text.boundary_type, BoundaryType::Punctuation);
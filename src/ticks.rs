@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
+
+/// A timestamp or a duration of the audio, in ticks of 100 nanoseconds.
+///
+/// The service reports all the offsets and durations in ticks: convert them with
+/// [`Ticks::as_millis`] or to a [`std::time::Duration`].
+///
+/// ```
+/// use azure_speech::Ticks;
+///
+/// let offset = Ticks::from_millis(1500);
+/// assert_eq!(offset.as_ticks(), 15_000_000);
+/// assert_eq!(std::time::Duration::from(offset), std::time::Duration::from_millis(1500));
+/// ```
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Ticks(u64);
+
+impl Ticks {
+    /// The number of ticks in a millisecond.
+    pub const PER_MILLISECOND: u64 = 10_000;
+
+    /// No time: the start of the audio.
+    pub const ZERO: Ticks = Ticks(0);
+
+    /// Create from a number of ticks of 100 nanoseconds.
+    pub const fn new(ticks: u64) -> Self {
+        Self(ticks)
+    }
+
+    /// Create from a number of milliseconds.
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(millis * Self::PER_MILLISECOND)
+    }
+
+    /// The number of ticks of 100 nanoseconds.
+    pub const fn as_ticks(self) -> u64 {
+        self.0
+    }
+
+    /// The number of whole milliseconds.
+    pub const fn as_millis(self) -> u64 {
+        self.0 / Self::PER_MILLISECOND
+    }
+
+    /// Subtract the other ticks, down to zero.
+    pub const fn saturating_sub(self, other: Ticks) -> Ticks {
+        Self(self.0.saturating_sub(other.0))
+    }
+}
+
+impl From<u64> for Ticks {
+    fn from(ticks: u64) -> Self {
+        Self(ticks)
+    }
+}
+
+impl From<Ticks> for std::time::Duration {
+    fn from(ticks: Ticks) -> Self {
+        std::time::Duration::from_nanos(ticks.0 * 100)
+    }
+}
+
+/// Rounded down to the tick.
+impl From<std::time::Duration> for Ticks {
+    fn from(duration: std::time::Duration) -> Self {
+        Self((duration.as_nanos() / 100) as u64)
+    }
+}
+
+impl fmt::Display for Ticks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", std::time::Duration::from(*self))
+    }
+}
+
+impl Add for Ticks {
+    type Output = Ticks;
+
+    fn add(self, other: Ticks) -> Ticks {
+        Self(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Ticks {
+    fn add_assign(&mut self, other: Ticks) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Ticks {
+    type Output = Ticks;
+
+    fn sub(self, other: Ticks) -> Ticks {
+        Self(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Ticks {
+    fn sub_assign(&mut self, other: Ticks) {
+        self.0 -= other.0;
+    }
+}
+
+impl Mul<u64> for Ticks {
+    type Output = Ticks;
+
+    fn mul(self, factor: u64) -> Ticks {
+        Self(self.0 * factor)
+    }
+}
+
+impl Div<u64> for Ticks {
+    type Output = Ticks;
+
+    fn div(self, divisor: u64) -> Ticks {
+        Self(self.0 / divisor)
+    }
+}
+
+impl Sum for Ticks {
+    fn sum<I: Iterator<Item = Ticks>>(iter: I) -> Ticks {
+        iter.fold(Ticks::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Ticks> for Ticks {
+    fn sum<I: Iterator<Item = &'a Ticks>>(iter: I) -> Ticks {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let ticks = Ticks::new(15_005_000);
+        assert_eq!(ticks.as_millis(), 1500);
+        assert_eq!(
+            std::time::Duration::from(ticks),
+            std::time::Duration::from_micros(1_500_500)
+        );
+        assert_eq!(
+            Ticks::from(std::time::Duration::from_nanos(1_550)),
+            15.into()
+        );
+        assert_eq!(Ticks::from_millis(2), Ticks::new(20_000));
+        assert_eq!(ticks.to_string(), "1.5005s");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut ticks = Ticks::new(100) + Ticks::new(50);
+        ticks -= Ticks::new(20);
+        assert_eq!(ticks, Ticks::new(130));
+        assert_eq!(ticks * 2 / 4, Ticks::new(65));
+        assert_eq!(Ticks::new(10).saturating_sub(ticks), Ticks::ZERO);
+        assert_eq!(
+            [Ticks::new(1), Ticks::new(2)].iter().sum::<Ticks>(),
+            Ticks::new(3)
+        );
+    }
+
+    #[test]
+    fn test_serde_is_transparent() {
+        assert_eq!(serde_json::to_string(&Ticks::new(500)).unwrap(), "500");
+        assert_eq!(
+            serde_json::from_str::<Ticks>("16500000").unwrap(),
+            Ticks::new(16_500_000)
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::callback::{BoxFuture, OnError, OnSessionEnded, OnSessionStarted};
+use crate::recognizer::{SpeechDetectedEvent, UnMatchEvent};
+use crate::translator::{Event, TranslatedEvent};
+use crate::RequestId;
+use std::future::Future;
+use std::sync::Arc;
+
+pub(crate) type OnTranslating = Box<dyn Fn(TranslatedEvent) -> BoxFuture>;
+pub(crate) type OnTranslated = Box<dyn Fn(TranslatedEvent) -> BoxFuture>;
+pub(crate) type OnUnMatch = Box<dyn Fn(UnMatchEvent) -> BoxFuture>;
+pub(crate) type OnStartDetected = Box<dyn Fn(SpeechDetectedEvent) -> BoxFuture>;
+pub(crate) type OnEndDetected = Box<dyn Fn(SpeechDetectedEvent) -> BoxFuture>;
+pub(crate) type OnSynthesising = Box<dyn Fn(RequestId, Vec<u8>) -> BoxFuture>;
+pub(crate) type OnSynthesised = Box<dyn Fn(RequestId) -> BoxFuture>;
+
+#[derive(Default, Clone)]
+pub struct Callback {
+    pub(crate) on_session_started: Option<Arc<OnSessionStarted>>,
+    pub(crate) on_error: Option<Arc<OnError>>,
+    pub(crate) on_session_ended: Option<Arc<OnSessionEnded>>,
+
+    pub(crate) on_translating: Option<Arc<OnTranslating>>,
+    pub(crate) on_translated: Option<Arc<OnTranslated>>,
+    pub(crate) on_un_match: Option<Arc<OnUnMatch>>,
+    pub(crate) on_start_detected: Option<Arc<OnStartDetected>>,
+    pub(crate) on_end_detected: Option<Arc<OnEndDetected>>,
+    pub(crate) on_synthesising: Option<Arc<OnSynthesising>>,
+    pub(crate) on_synthesised: Option<Arc<OnSynthesised>>,
+}
+
+impl Callback {
+    pub fn on_session_start<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(RequestId) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_session_started = Some(Arc::new(Box::new(move |str| Box::pin(func(str)))));
+        self
+    }
+
+    pub fn on_session_end<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(RequestId) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_session_ended = Some(Arc::new(Box::new(move |str| Box::pin(func(str)))));
+        self
+    }
+
+    pub fn on_error<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(RequestId, crate::Error) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_error = Some(Arc::new(Box::new(move |request, err| {
+            Box::pin(func(request, err))
+        })));
+        self
+    }
+
+    pub fn on_translating<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(TranslatedEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_translating = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
+        self
+    }
+
+    pub fn on_translated<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(TranslatedEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_translated = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
+        self
+    }
+
+    pub fn on_un_match<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(UnMatchEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_un_match = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
+        self
+    }
+
+    pub fn on_start_detected<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(SpeechDetectedEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_start_detected = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
+        self
+    }
+
+    pub fn on_end_detected<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(SpeechDetectedEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_end_detected = Some(Arc::new(Box::new(move |event| Box::pin(func(event)))));
+        self
+    }
+
+    pub fn on_synthesising<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(RequestId, Vec<u8>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_synthesising = Some(Arc::new(Box::new(move |request_id, audio| {
+            Box::pin(func(request_id, audio))
+        })));
+        self
+    }
+
+    pub fn on_synthesised<F, Fut>(mut self, func: F) -> Self
+    where
+        F: Fn(RequestId) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_synthesised = Some(Arc::new(Box::new(move |request_id| {
+            Box::pin(func(request_id))
+        })));
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::callback::Callback for Callback {
+    type Item = crate::Result<Event>;
+
+    #[allow(clippy::manual_async_fn)]
+    fn on_event(&self, item: Self::Item) -> impl Future<Output = ()> {
+        async move {
+            match &item {
+                Ok(Event::SessionStarted(event)) => {
+                    tracing::debug!("Session started");
+                    if let Some(f) = self.on_session_started.as_ref() {
+                        f(event.request_id()).await
+                    }
+                }
+                Ok(Event::SessionEnded(event)) => {
+                    tracing::debug!("Session ended");
+                    if let Some(f) = self.on_session_ended.as_ref() {
+                        f(event.request_id()).await
+                    }
+                }
+
+                Ok(Event::Translating(event)) => {
+                    if let Some(f) = self.on_translating.as_ref() {
+                        f(event.clone()).await
+                    }
+                }
+
+                Ok(Event::Translated(event)) => {
+                    if let Some(f) = self.on_translated.as_ref() {
+                        f(event.clone()).await
+                    }
+                }
+
+                Ok(Event::UnMatch(event)) => {
+                    if let Some(f) = self.on_un_match.as_ref() {
+                        f(event.clone()).await
+                    }
+                }
+
+                Ok(Event::EndDetected(event)) => {
+                    if let Some(f) = self.on_end_detected.as_ref() {
+                        f(event.clone()).await
+                    }
+                }
+
+                Ok(Event::StartDetected(event)) => {
+                    if let Some(f) = self.on_start_detected.as_ref() {
+                        f(event.clone()).await
+                    }
+                }
+
+                Ok(Event::Synthesising(request_id, audio)) => {
+                    tracing::debug!("Synthesising audio: {:?}", audio.len());
+                    if let Some(f) = self.on_synthesising.as_ref() {
+                        f(*request_id, audio.clone()).await
+                    }
+                }
+
+                Ok(Event::Synthesised(request_id)) => {
+                    tracing::debug!("Synthesised");
+                    if let Some(f) = self.on_synthesised.as_ref() {
+                        f(*request_id).await
+                    }
+                }
+
+                Err(e) => {
+                    tracing::error!("Error: {:?}", e);
+                }
+            }
+        }
+    }
+}
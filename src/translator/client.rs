@@ -0,0 +1,354 @@
+use crate::connector::Client as BaseClient;
+use crate::recognizer::client::{
+    resume_on_connection_lost, stream_audio, AudioControl, AudioInput,
+};
+use crate::recognizer::session::Session;
+use crate::recognizer::utils::create_speech_config_message;
+use crate::recognizer::{
+    AudioDevice, AudioFormat, ChannelMode, Confidence, NoMatchReason, OutputFormat,
+    PrimaryLanguage, PullAudioInputStream, Recognition, RecognitionHandle, SessionEvent,
+    SpeechDetectedEvent, UnMatchEvent,
+};
+use crate::translator::message::{
+    SynthesisStatus, TranslationHypothesis, TranslationPhrase, TranslationStatus,
+    TranslationSynthesisEnd,
+};
+use crate::translator::utils::create_translation_context_message;
+use crate::translator::{Config, Event, Translated, TranslatedEvent};
+use crate::utils::get_azure_hostname_from_region;
+use crate::{stream_ext::StreamExt, Auth, Data, Message};
+use tokio_stream::{Stream, StreamExt as _};
+use url::Url;
+
+#[derive(Clone)]
+pub struct Client {
+    pub client: BaseClient,
+    pub config: Config,
+}
+
+impl Client {
+    pub fn new(client: BaseClient, config: Config) -> Self {
+        Self { client, config }
+    }
+
+    pub async fn connect(auth: Auth, config: Config) -> crate::Result<Self> {
+        let base_url = format!(
+            "wss://{}.s2s.speech{}/speech/translation/cognitiveservices/v1",
+            auth.region,
+            get_azure_hostname_from_region(&auth.region),
+        );
+        let mut url = Url::parse(&base_url)?;
+
+        let source = &config.source;
+        let language = source
+            .languages
+            .first()
+            .ok_or_else(|| crate::Error::IOError("No language specified.".to_string()))?;
+        source.validate_custom_models()?;
+        if config.target_languages.is_empty() {
+            return Err(crate::Error::IOError(
+                "No target language specified.".to_string(),
+            ));
+        }
+
+        url.query_pairs_mut()
+            .append_pair("from", language.to_string().as_str())
+            .append_pair("to", config.target_languages.join(",").as_str())
+            .append_pair("format", source.output_format.as_str())
+            .append_pair("profanity", source.profanity.as_str())
+            .append_pair("storeAudio", &source.store_audio.to_string());
+        if source.output_format == OutputFormat::Detailed {
+            url.query_pairs_mut()
+                .append_pair("wordLevelTimestamps", "true");
+        }
+        if source.is_language_detection_enabled() {
+            url.query_pairs_mut().append_pair("lidEnabled", "true");
+        }
+        if let Some(ref voice) = config.voice {
+            url.query_pairs_mut()
+                .append_pair("features", "texttospeech")
+                .append_pair("voice", voice.as_str());
+        }
+        if let Some(endpoint_id) = source.custom_endpoint_id() {
+            url.query_pairs_mut().append_pair("cid", endpoint_id);
+        }
+        if let Some(ref connection_id) = source.connection_id {
+            url.query_pairs_mut()
+                .append_pair("X-ConnectionId", connection_id);
+        }
+
+        let ws_client = tokio_websockets::ClientBuilder::new()
+            .uri(url.as_str())
+            .unwrap()
+            .add_header(
+                "Ocp-Apim-Subscription-Key".try_into().unwrap(),
+                auth.subscription.to_string().as_str().try_into().unwrap(),
+            )?
+            .add_header(
+                "X-ConnectionId".try_into().unwrap(),
+                uuid::Uuid::new_v4().to_string().try_into().unwrap(),
+            )?;
+
+        let client = BaseClient::connect(ws_client).await?;
+        Ok(Self::new(client, config))
+    }
+
+    pub async fn disconnect(&self) -> crate::Result<()> {
+        self.client.disconnect().await
+    }
+
+    pub async fn translate_file(
+        &self,
+        path: impl Into<std::path::PathBuf>,
+    ) -> crate::Result<Recognition<impl Stream<Item = crate::Result<Event>>>> {
+        let path = path.into();
+        let file = tokio::fs::File::open(&path).await?;
+        // The extension is only a hint: the format is detected from the content.
+        let hint = path.extension().and_then(|ext| ext.try_into().ok());
+
+        let (audio, audio_format) = PullAudioInputStream::detect_format(file, hint).await?;
+        self.translate(audio, audio_format, AudioDevice::file())
+            .await
+    }
+
+    /// Translate the given audio stream.
+    ///
+    /// The returned [`Recognition`] is the stream of `translator::Event`. It ends after the last
+    /// turn once the audio stream is completed, or when it is stopped or aborted.
+    ///
+    /// The channels are always translated mixed, so [`ChannelMode::Split`] is rejected with a
+    /// [`ConfigError`](crate::Error::ConfigError).
+    pub async fn translate<A>(
+        &self,
+        audio: A,
+        audio_format: AudioFormat,
+        audio_device: AudioDevice,
+    ) -> crate::Result<Recognition<impl Stream<Item = crate::Result<Event>>>>
+    where
+        A: Stream<Item = Vec<u8>> + Sync + Send + Unpin + 'static,
+    {
+        if matches!(self.config.source.channel_mode, ChannelMode::Split) {
+            return Err(crate::Error::ConfigError(
+                "The translator does not support the split channel mode.".to_string(),
+            ));
+        }
+
+        let messages = self.client.stream().await?;
+        let session = Session::new();
+        let client = self.client.clone();
+        let input = AudioInput::open(audio, audio_format, &self.config.source).await?;
+
+        // The speech configuration and context are sent at the start of each connection.
+        let spec = input.spec().copied();
+        let config = self.config.clone();
+        let control_tx = stream_audio(client, session.clone(), input, move |request_id| {
+            Ok(vec![
+                create_speech_config_message(
+                    request_id.clone(),
+                    &config.source,
+                    &audio_device,
+                    spec.as_ref(),
+                ),
+                create_translation_context_message(request_id, &config)?,
+            ])
+        })
+        .await?;
+        let handle = RecognitionHandle::new(control_tx.clone());
+
+        let session_filter = session.clone();
+        let session_clone = session.clone();
+        let control_resume = control_tx.clone();
+        let messages = messages.filter(move |msg| match msg {
+            Ok(m) => m.id == session_filter.request_id().to_string(),
+            Err(_) => true,
+        });
+        // A lost connection is resumed, with the audio sent again from the last final phrase.
+        let messages = Box::pin(futures_util::StreamExt::then(messages, move |msg| {
+            let control = control_resume.clone();
+            async move { resume_on_connection_lost(msg, &control).await }
+        }));
+        let output_stream = messages
+            .filter_map(move |msg| match msg? {
+                Ok(m) => convert_message_to_event(m, &session_clone),
+                Err(e) => Some(Err(e)),
+            })
+            .map(move |event| {
+                if let Ok(Event::SessionEnded(_)) = event {
+                    let _ = control_tx.try_send(AudioControl::Restart);
+                }
+                event
+            })
+            // The last turn ends after all the audio has been sent.
+            .stop_after(move |event| {
+                event.is_err()
+                    || matches!(event, Ok(Event::SessionEnded(_))) && session.is_audio_completed()
+            });
+
+        let aborted = handle.clone();
+        let output_stream = futures_util::StreamExt::take_until(
+            output_stream,
+            Box::pin(async move { aborted.aborted().await }),
+        );
+
+        Ok(Recognition::new(output_stream, handle))
+    }
+}
+
+fn convert_message_to_event(message: Message, session: &Session) -> Option<crate::Result<Event>> {
+    match (message.path.as_str(), message.data, message.headers) {
+        ("turn.start", _, _) => Some(Ok(Event::SessionStarted(SessionEvent::new(
+            session.request_id(),
+        )))),
+        ("speech.startdetected", Data::Text(Some(data)), _) => {
+            serde_json::from_str::<crate::recognizer::message::SpeechStartDetected>(&data)
+                .map(|v| {
+                    Event::StartDetected(SpeechDetectedEvent::new(
+                        session.request_id(),
+                        session.audio_offset_at(v.offset),
+                    ))
+                })
+                .map(Ok)
+                .ok()
+        }
+        ("speech.enddetected", Data::Text(Some(data)), _) => {
+            let value =
+                serde_json::from_str::<crate::recognizer::message::SpeechEndDetected>(&data)
+                    .unwrap_or_default();
+            Some(Ok(Event::EndDetected(SpeechDetectedEvent::new(
+                session.request_id(),
+                session.audio_offset_at(value.offset),
+            ))))
+        }
+        ("translation.hypothesis", Data::Text(Some(data)), _) => {
+            match serde_json::from_str::<TranslationHypothesis>(&data) {
+                Ok(value) => {
+                    let offset = session.audio_offset_at(value.offset);
+                    session.on_hypothesis_received(offset);
+                    Some(Ok(Event::Translating(TranslatedEvent::new(
+                        session.request_id(),
+                        Translated {
+                            text: value.text,
+                            translations: value
+                                .translation
+                                .translations
+                                .into_iter()
+                                .map(|t| (t.language, t.text))
+                                .collect(),
+                            primary_language: value.primary_language.map(|l| {
+                                PrimaryLanguage::new(
+                                    l.language.into(),
+                                    l.confidence.map_or(Confidence::Unknown, |c| c.into()),
+                                )
+                            }),
+                        },
+                        offset,
+                        value.duration,
+                        data,
+                    ))))
+                }
+                Err(e) => Some(Err(crate::Error::ParseError(e.to_string()))),
+            }
+        }
+        ("translation.phrase", Data::Text(Some(data)), _) => {
+            match serde_json::from_str::<TranslationPhrase>(&data) {
+                Ok(value) => {
+                    let offset = session.audio_offset_at(value.offset.unwrap_or_default());
+                    let duration = value.duration.unwrap_or_default();
+                    if value.recognition_status.is_end_of_dictation() {
+                        return None;
+                    }
+                    session.on_phrase_recognized(value.offset.unwrap_or_default(), duration);
+                    if let Some(reason) = Option::<NoMatchReason>::from(&value.recognition_status) {
+                        return Some(Ok(Event::UnMatch(UnMatchEvent::new(
+                            session.request_id(),
+                            reason,
+                            offset,
+                            duration,
+                            data,
+                        ))));
+                    }
+                    if let Some(err) = Option::<crate::Error>::from(&value.recognition_status) {
+                        return Some(Err(err));
+                    }
+
+                    let translation = value.translation.unwrap_or_default();
+                    if translation.translation_status == TranslationStatus::Error {
+                        return Some(Err(crate::Error::RuntimeError(
+                            translation
+                                .failure_reason
+                                .unwrap_or_else(|| "Translation failed.".to_string()),
+                        )));
+                    }
+
+                    Some(Ok(Event::Translated(TranslatedEvent::new(
+                        session.request_id(),
+                        Translated {
+                            text: value.text.unwrap_or_default(),
+                            translations: translation
+                                .translations
+                                .into_iter()
+                                .map(|t| (t.language, t.text))
+                                .collect(),
+                            primary_language: value.primary_language.map(|l| {
+                                PrimaryLanguage::new(
+                                    l.language.into(),
+                                    l.confidence.map_or(Confidence::Unknown, |c| c.into()),
+                                )
+                            }),
+                        },
+                        offset,
+                        duration,
+                        data,
+                    ))))
+                }
+                Err(e) => Some(Err(crate::Error::ParseError(e.to_string()))),
+            }
+        }
+        ("translation.synthesis", Data::Binary(Some(audio)), _) => {
+            Some(Ok(Event::Synthesising(session.request_id(), audio)))
+        }
+        ("translation.synthesis.end", Data::Text(Some(data)), _) => {
+            match serde_json::from_str::<TranslationSynthesisEnd>(&data) {
+                Ok(value) if value.synthesis_status == SynthesisStatus::Error => {
+                    Some(Err(crate::Error::RuntimeError(
+                        value
+                            .failure_reason
+                            .unwrap_or_else(|| "Synthesis failed.".to_string()),
+                    )))
+                }
+                Ok(_) => Some(Ok(Event::Synthesised(session.request_id()))),
+                Err(e) => Some(Err(crate::Error::ParseError(e.to_string()))),
+            }
+        }
+        ("turn.end", _, _) => Some(Ok(Event::SessionEnded(SessionEvent::new(
+            session.request_id(),
+        )))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer;
+
+    #[tokio::test]
+    async fn test_translate_rejects_split_channels() {
+        // The connection is never used: the configuration is rejected first.
+        let client = Client::new(
+            BaseClient::disconnected(),
+            Config::default()
+                .set_source(recognizer::Config::default().set_channel_mode(ChannelMode::Split))
+                .add_target_language("de"),
+        );
+
+        let result = client
+            .translate(
+                tokio_stream::pending(),
+                AudioFormat::Mp3,
+                AudioDevice::unknown(),
+            )
+            .await;
+        assert!(matches!(result, Err(crate::Error::ConfigError(_))));
+    }
+}
@@ -0,0 +1,56 @@
+use crate::recognizer;
+use crate::synthesizer::Voice;
+
+/// The configuration for the translator.
+///
+/// The source speech is recognized with the recognizer configuration,
+/// then translated into each of the target languages.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    pub(crate) source: recognizer::Config,
+
+    pub(crate) target_languages: Vec<String>,
+
+    pub(crate) voice: Option<Voice>,
+}
+
+impl Config {
+    /// Set the recognition configuration of the source speech.
+    ///
+    /// Use it to configure the source language(s), the phrases, the profanity and the device.
+    /// The language identification only mode and the split channel mode are not supported: the
+    /// translation fails with a [`ConfigError`](crate::Error::ConfigError).
+    pub fn set_source(mut self, source: recognizer::Config) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Set the language of the source speech.
+    pub fn set_language(mut self, language: recognizer::Language) -> Self {
+        self.source = self.source.set_language(language);
+        self
+    }
+
+    /// Add a language to translate the speech into.
+    ///
+    /// The language is the BCP-47 code of the text language, like `de` or `zh-Hans`.
+    pub fn add_target_language(mut self, language: impl Into<String>) -> Self {
+        self.target_languages.push(language.into());
+        self
+    }
+
+    /// Set the languages to translate the speech into.
+    pub fn set_target_languages(mut self, languages: Vec<String>) -> Self {
+        self.target_languages = languages;
+        self
+    }
+
+    /// Synthesize the translated text with the given voice.
+    ///
+    /// The voice language must be one of the target languages.
+    /// The audio is received with the `Synthesising` events.
+    pub fn set_voice(mut self, voice: Voice) -> Self {
+        self.voice = Some(voice);
+        self
+    }
+}
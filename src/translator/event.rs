@@ -0,0 +1,159 @@
+use crate::recognizer::{
+    Duration, Offset, PrimaryLanguage, RawMessage, SessionEvent, SpeechDetectedEvent, UnMatchEvent,
+};
+use crate::RequestId;
+use std::collections::HashMap;
+
+/// Translator events.
+///
+/// The events are used to notify the user of the progress of the speech translation.
+/// The events of the speech wrap a struct, whose fields are read with its accessors.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Event {
+    /// The session started.
+    SessionStarted(SessionEvent),
+
+    /// The session ended.
+    SessionEnded(SessionEvent),
+
+    /// The speech recognition started.
+    StartDetected(SpeechDetectedEvent),
+    /// The speech recognition ended.
+    EndDetected(SpeechDetectedEvent),
+
+    /// Translating event, with the intermediate translations.
+    Translating(TranslatedEvent),
+
+    /// Translated event, with the final translations.
+    Translated(TranslatedEvent),
+
+    /// UnMatch event.
+    /// This event is triggered when the speech recognition does not match any text.
+    UnMatch(UnMatchEvent),
+
+    /// Raw audio chunk of the synthesized translation.
+    /// Only received when a voice is configured.
+    /// With the `serde` feature, the audio is serialized as a base64 string.
+    Synthesising(
+        RequestId,
+        #[cfg_attr(feature = "serde", serde(with = "crate::utils::base64_audio"))] Vec<u8>,
+    ),
+    /// The synthesis of the translation has finished.
+    Synthesised(RequestId),
+}
+
+/// A phrase translated, or the current hypothesis of the phrase being translated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct TranslatedEvent {
+    pub(crate) request_id: RequestId,
+    pub(crate) result: Translated,
+    pub(crate) offset: Offset,
+    pub(crate) duration: Duration,
+    pub(crate) raw: RawMessage,
+}
+
+impl TranslatedEvent {
+    pub(crate) fn new(
+        request_id: RequestId,
+        result: Translated,
+        offset: Offset,
+        duration: Duration,
+        raw: RawMessage,
+    ) -> Self {
+        Self {
+            request_id,
+            result,
+            offset,
+            duration,
+            raw,
+        }
+    }
+
+    /// The id of the request.
+    pub fn request_id(&self) -> RequestId {
+        self.request_id
+    }
+
+    /// The recognized text and its translations.
+    pub fn result(&self) -> &Translated {
+        &self.result
+    }
+
+    /// The offset of the phrase, from the start of the audio.
+    pub fn offset(&self) -> Offset {
+        self.offset
+    }
+
+    /// The duration of the phrase.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The message received from the service.
+    pub fn raw(&self) -> &RawMessage {
+        &self.raw
+    }
+
+    /// Take the recognized text and its translations.
+    pub fn into_result(self) -> Translated {
+        self.result
+    }
+}
+
+/// The translated text.
+///
+/// Contains the recognized text of the source speech and its translations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Translated {
+    /// The recognized text, in the source language.
+    pub text: String,
+    /// The translated text, keyed by target language.
+    pub translations: HashMap<String, String>,
+    /// The primary language of the recognized text.
+    pub primary_language: Option<PrimaryLanguage>,
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::recognizer::{Confidence, Language, NoMatchReason};
+    use crate::Ticks;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_events_round_trip() {
+        let events = vec![
+            Event::SessionStarted(SessionEvent::new(Uuid::nil())),
+            Event::Translated(TranslatedEvent::new(
+                Uuid::nil(),
+                Translated {
+                    text: "Hello.".to_string(),
+                    translations: HashMap::from([("it".to_string(), "Ciao.".to_string())]),
+                    primary_language: Some(PrimaryLanguage::new(Language::EnUs, Confidence::Low)),
+                },
+                Ticks::new(100),
+                Ticks::new(500),
+                "{}".to_string(),
+            )),
+            Event::UnMatch(UnMatchEvent::new(
+                Uuid::nil(),
+                NoMatchReason::InitialSilenceTimeout,
+                Ticks::ZERO,
+                Ticks::new(500),
+                String::new(),
+            )),
+            Event::EndDetected(SpeechDetectedEvent::new(Uuid::nil(), Ticks::new(600))),
+            Event::Synthesising(Uuid::nil(), vec![0, 128, 255]),
+        ];
+
+        for event in events {
+            let json = serde_json::to_string(&event).unwrap();
+            assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod translation;
+mod translation_synthesis_end;
+
+pub(crate) use translation::*;
+pub(crate) use translation_synthesis_end::*;
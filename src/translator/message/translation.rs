@@ -0,0 +1,58 @@
+use crate::recognizer::message::{Language, RecognitionStatus};
+use crate::recognizer::{Duration, Offset};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct TranslationHypothesis {
+    #[serde(rename = "Text")]
+    pub(crate) text: String,
+    #[serde(rename = "Offset")]
+    pub(crate) offset: Offset,
+    #[serde(rename = "Duration")]
+    pub(crate) duration: Duration,
+    #[serde(rename = "PrimaryLanguage")]
+    pub(crate) primary_language: Option<Language>,
+    #[serde(rename = "Translation")]
+    pub(crate) translation: Translation,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct TranslationPhrase {
+    #[serde(rename = "RecognitionStatus")]
+    pub(crate) recognition_status: RecognitionStatus,
+    #[serde(rename = "Text")]
+    pub(crate) text: Option<String>,
+    #[serde(rename = "Offset")]
+    pub(crate) offset: Option<Offset>,
+    #[serde(rename = "Duration")]
+    pub(crate) duration: Option<Duration>,
+    #[serde(rename = "PrimaryLanguage")]
+    pub(crate) primary_language: Option<Language>,
+    #[serde(rename = "Translation")]
+    pub(crate) translation: Option<Translation>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub(crate) struct Translation {
+    #[serde(rename = "TranslationStatus")]
+    pub(crate) translation_status: TranslationStatus,
+    #[serde(rename = "Translations", default)]
+    pub(crate) translations: Vec<TranslatedText>,
+    #[serde(rename = "FailureReason")]
+    pub(crate) failure_reason: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct TranslatedText {
+    #[serde(rename = "Language")]
+    pub(crate) language: String,
+    #[serde(rename = "Text")]
+    pub(crate) text: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) enum TranslationStatus {
+    #[default]
+    Success,
+    Error,
+}
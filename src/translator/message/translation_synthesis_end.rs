@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct TranslationSynthesisEnd {
+    #[serde(rename = "SynthesisStatus")]
+    pub(crate) synthesis_status: SynthesisStatus,
+    #[serde(rename = "FailureReason")]
+    pub(crate) failure_reason: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SynthesisStatus {
+    Success,
+    Error,
+}
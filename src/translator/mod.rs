@@ -0,0 +1,48 @@
+//! Speech translation module.
+//!
+//! This module provides functionality to translate speech into text of other languages,
+//! and optionally into synthesized speech, using Azure Speech Services.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use azure_speech::{translator, Auth, stream::StreamExt};
+//! use std::env;
+//! use std::error::Error;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn Error>> {
+//!     let auth = Auth::from_subscription(
+//!         env::var("AZURE_REGION").expect("Region set on AZURE_REGION env"),
+//!         env::var("AZURE_SUBSCRIPTION_KEY").expect("Subscription set on AZURE_SUBSCRIPTION_KEY env"),
+//!     );
+//!
+//!     let config = translator::Config::default()
+//!         .add_target_language("de")
+//!         .add_target_language("fr");
+//!
+//!     let client = translator::Client::connect(auth, config).await?;
+//!     let mut stream = client
+//!         .translate_file("tests/audios/examples_sample_files_turn_on_the_lamp.wav")
+//!         .await?;
+//!
+//!     while let Some(event) = stream.next().await {
+//!         if let Ok(translator::Event::Translated(translated)) = event {
+//!             tracing::info!("Translations: {:?}", translated.result().translations);
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+mod callback;
+mod client;
+mod config;
+mod event;
+mod message;
+mod utils;
+
+pub use callback::*;
+pub use client::*;
+pub use config::*;
+pub use event::*;
@@ -0,0 +1,96 @@
+use crate::recognizer::utils::{create_speech_context, make_speech_context_message};
+use crate::translator::Config;
+use serde_json::json;
+use tokio_websockets::Message;
+
+pub(crate) fn create_translation_context_message(
+    request_id: String,
+    config: &Config,
+) -> crate::Result<Message> {
+    // Without the recognition of the speech there is nothing to translate.
+    if config.source.language_identification_only {
+        return Err(crate::Error::ConfigError(
+            "The translator does not support the language identification only mode.".to_string(),
+        ));
+    }
+
+    let mut context = create_speech_context(&config.source)?;
+
+    // When the source language is detected, the service needs to know
+    // that the detected speech should be translated, not only recognized.
+    if context.get("languageId").is_some() {
+        context["languageId"]["onSuccess"] = json!({ "action": "Translate" });
+        context["phraseDetection"]["onInterim"] = json!({ "action": "Translate" });
+        context["phraseDetection"]["onSuccess"] = json!({ "action": "Translate" });
+    }
+
+    Ok(make_speech_context_message(request_id, &context))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::{self, Language, LanguageDetectMode};
+    use crate::{Data, Message as EzMessage};
+    use serde_json::Value;
+
+    fn context_of(config: &Config) -> Value {
+        let msg = EzMessage::try_from(
+            create_translation_context_message("id".to_string(), config).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(msg.path, "speech.context");
+        assert_eq!(msg.id, "id");
+
+        match msg.data {
+            Data::Text(Some(ref body)) => serde_json::from_str(body).unwrap(),
+            _ => panic!("expected text body"),
+        }
+    }
+
+    #[test]
+    fn test_create_translation_context_message_single_language() {
+        let config = Config::default().add_target_language("de");
+        assert_eq!(context_of(&config), json!({}));
+    }
+
+    #[test]
+    fn test_create_translation_context_message_detect_languages() {
+        let config = Config::default()
+            .set_source(recognizer::Config::default().set_detect_languages(
+                vec![Language::EnUs, Language::ItIt],
+                LanguageDetectMode::Continuous,
+            ))
+            .add_target_language("de");
+        let context = context_of(&config);
+
+        assert_eq!(
+            context["languageId"]["onSuccess"],
+            json!({ "action": "Translate" })
+        );
+        assert_eq!(
+            context["phraseDetection"]["onInterim"],
+            json!({ "action": "Translate" })
+        );
+        assert_eq!(
+            context["phraseDetection"]["onSuccess"],
+            json!({ "action": "Translate" })
+        );
+    }
+
+    #[test]
+    fn test_create_translation_context_message_rejects_identify_languages() {
+        let config = Config::default()
+            .set_source(recognizer::Config::default().set_identify_languages(
+                vec![Language::EnUs, Language::ItIt],
+                LanguageDetectMode::Continuous,
+            ))
+            .add_target_language("de");
+
+        assert!(matches!(
+            create_translation_context_message("id".to_string(), &config),
+            Err(crate::Error::ConfigError(_))
+        ));
+    }
+}
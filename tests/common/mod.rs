@@ -72,6 +72,7 @@ pub fn make_text_payload(headers: Vec<(String, String)>, data: Option<&str>) ->
     format!("{header_string}\r\n{}", data.unwrap_or(""))
 }
 
+#[allow(dead_code)]
 pub fn make_binary_payload(headers: Vec<(String, String)>, data: Option<&[u8]>) -> Vec<u8> {
     let mut header_string = String::new();
     for (k, v) in headers {
@@ -1,6 +1,6 @@
 mod common;
 
-use azure_speech::{recognizer, Data, Message};
+use azure_speech::{recognizer, Data, Message, Ticks};
 use futures_util::{SinkExt, StreamExt};
 use http::Uri;
 use std::collections::VecDeque;
@@ -275,11 +275,11 @@ async fn integration_test_recognizer() {
                         }
                     }
 
-                    match tokio::time::timeout(std::time::Duration::from_millis(10), stream.next())
+                    if tokio::time::timeout(std::time::Duration::from_millis(10), stream.next())
                         .await
+                        .is_ok()
                     {
-                        Ok(_) => panic!("Not expecting anything new."),
-                        _ => {}
+                        panic!("Not expecting anything new.");
                     }
                 })
             },
@@ -367,3 +367,1488 @@ async fn functional_disconnect_reconnect_recognizer() {
         .collect::<Vec<_>>()
         .await;
 }
+
+fn recognize_once_server(
+    phrase: &'static str,
+    after_phrase: tokio::sync::mpsc::UnboundedSender<Message>,
+) -> impl Fn(WebSocketStream<TcpStream>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Clone {
+    move |mut ws: WebSocketStream<TcpStream>| {
+        let after_phrase = after_phrase.clone();
+        Box::pin(async move {
+            let config = match ws.next().await {
+                Some(Ok(msg)) => Message::try_from(msg).unwrap(),
+                _ => return,
+            };
+            let request_id = config.id.clone();
+            match config.data {
+                Data::Text(Some(body)) => {
+                    let v: serde_json::Value = serde_json::from_str(&body).unwrap();
+                    assert_eq!(v["recognition"], "interactive");
+                }
+                _ => panic!("expected text body"),
+            }
+
+            // speech.context and audio header
+            ws.next().await;
+            ws.next().await;
+
+            use crate::common::make_text_payload;
+
+            for (path, data) in [("turn.start", None), ("speech.phrase", Some(phrase))] {
+                let payload = make_text_payload(
+                    vec![
+                        ("X-RequestId".to_string(), request_id.clone()),
+                        ("Path".to_string(), path.to_string()),
+                    ],
+                    data,
+                );
+                ws.send(tokio_websockets::Message::text(payload))
+                    .await
+                    .unwrap();
+            }
+
+            // Forward the next message received, to verify that the audio is stopped.
+            if let Some(Ok(msg)) = ws.next().await {
+                let _ = after_phrase.send(Message::try_from(msg).unwrap());
+            }
+
+            let _ = ws.close().await;
+        })
+    }
+}
+
+#[tokio::test]
+async fn functional_recognize_once_recognized() {
+    let address = "127.0.0.1:4569";
+    let (tx, mut after_phrase) = tokio::sync::mpsc::unbounded_channel();
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![recognize_once_server(
+            r#"{"RecognitionStatus":"Success","Offset":100,"Duration":500,"DisplayText":"Turn on the lamp."}"#,
+            tx,
+        )]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(
+        client,
+        recognizer::Config::default()
+            .set_recognition_mode(recognizer::RecognitionMode::Interactive),
+    );
+
+    let result = recognizer
+        .recognize_once(
+            tokio_stream::pending(),
+            recognizer::AudioFormat::Mp3,
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap();
+
+    match result {
+        recognizer::RecognizeOnceResult::Recognized(event) => {
+            assert_eq!(event.result().text, "Turn on the lamp.");
+            assert_eq!(
+                (event.offset(), event.duration()),
+                (Ticks::new(100), Ticks::new(500))
+            );
+        }
+        r => panic!("Expected Recognized, got {:?}", r),
+    }
+
+    // The audio stream is still open, but the end of audio must be sent after the phrase.
+    let end_of_audio = tokio::time::timeout(std::time::Duration::from_secs(1), after_phrase.recv())
+        .await
+        .expect("end of audio")
+        .unwrap();
+    assert_eq!(end_of_audio.path, "audio");
+    assert_eq!(end_of_audio.data, Data::Binary(None));
+}
+
+#[tokio::test]
+async fn functional_recognize_once_no_match() {
+    let address = "127.0.0.1:4570";
+    let (tx, mut after_phrase) = tokio::sync::mpsc::unbounded_channel();
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![recognize_once_server(
+            r#"{"RecognitionStatus":"InitialSilenceTimeout","Offset":0,"Duration":50000000}"#,
+            tx,
+        )]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    // The client is connected to the conversation endpoint by default.
+    let result = recognizer::Client::new(client.clone(), recognizer::Config::default())
+        .recognize_once(
+            tokio_stream::pending(),
+            recognizer::AudioFormat::Mp3,
+            recognizer::AudioDevice::unknown(),
+        )
+        .await;
+    assert!(matches!(result, Err(azure_speech::Error::ConfigError(_))));
+
+    // The channels cannot be split for a single phrase.
+    let result = recognizer::Client::new(
+        client.clone(),
+        recognizer::Config::default()
+            .set_recognition_mode(recognizer::RecognitionMode::Interactive)
+            .set_channel_mode(recognizer::ChannelMode::Split),
+    )
+    .recognize_once(
+        tokio_stream::pending(),
+        recognizer::AudioFormat::Mp3,
+        recognizer::AudioDevice::unknown(),
+    )
+    .await;
+    assert!(matches!(result, Err(azure_speech::Error::ConfigError(_))));
+
+    let recognizer = recognizer::Client::new(
+        client,
+        recognizer::Config::default()
+            .set_recognition_mode(recognizer::RecognitionMode::Interactive),
+    );
+
+    let result = recognizer
+        .recognize_once(
+            tokio_stream::pending(),
+            recognizer::AudioFormat::Mp3,
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap();
+
+    match result {
+        recognizer::RecognizeOnceResult::NoMatch(event) => {
+            assert_eq!(
+                event.reason(),
+                recognizer::NoMatchReason::InitialSilenceTimeout
+            );
+            assert_eq!(
+                (event.offset(), event.duration()),
+                (Ticks::ZERO, Ticks::new(50000000))
+            );
+        }
+        r => panic!("Expected NoMatch, got {:?}", r),
+    }
+
+    // The audio stream is still open, but the end of audio must be sent after the phrase.
+    let end_of_audio = tokio::time::timeout(std::time::Duration::from_secs(1), after_phrase.recv())
+        .await
+        .expect("end of audio")
+        .unwrap();
+    assert_eq!(end_of_audio.path, "audio");
+    assert_eq!(end_of_audio.data, Data::Binary(None));
+}
+
+#[tokio::test]
+async fn functional_recognize_stop() {
+    let address = "127.0.0.1:4571";
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![|mut ws: WebSocketStream<TcpStream>| -> Pin<
+            Box<dyn Future<Output = ()> + Send>,
+        > {
+            Box::pin(async move {
+                let request_id = match ws.next().await {
+                    Some(Ok(msg)) => Message::try_from(msg).unwrap().id,
+                    _ => return,
+                };
+
+                // speech.context and audio header
+                ws.next().await;
+                ws.next().await;
+
+                use crate::common::make_text_payload;
+
+                let send = |path: &str, data: Option<&str>| {
+                    tokio_websockets::Message::text(make_text_payload(
+                        vec![
+                            ("X-RequestId".to_string(), request_id.clone()),
+                            ("Path".to_string(), path.to_string()),
+                        ],
+                        data,
+                    ))
+                };
+
+                ws.send(send("turn.start", None)).await.unwrap();
+
+                // wait for the end of audio sent by stop().
+                while let Some(Ok(msg)) = ws.next().await {
+                    if Message::try_from(msg).unwrap().data == Data::Binary(None) {
+                        break;
+                    }
+                }
+
+                ws.send(send(
+                    "speech.phrase",
+                    Some(r#"{"RecognitionStatus":"Success","Offset":0,"Duration":100,"DisplayText":"Stop."}"#),
+                ))
+                .await
+                .unwrap();
+                ws.send(send("turn.end", None)).await.unwrap();
+
+                // keep the connection open.
+                while ws.next().await.is_some() {}
+            })
+        }]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(client, recognizer::Config::default());
+
+    let mut events = recognizer
+        .recognize(
+            tokio_stream::pending(),
+            recognizer::AudioFormat::Mp3,
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        events.next().await,
+        Some(Ok(recognizer::Event::SessionStarted(_)))
+    ));
+
+    events.stop().await;
+
+    let rest = tokio::time::timeout(
+        std::time::Duration::from_secs(1),
+        events.collect::<Vec<_>>(),
+    )
+    .await
+    .expect("stream to end after stop");
+
+    assert_eq!(rest.len(), 2);
+    assert!(matches!(rest[0], Ok(recognizer::Event::Recognized(..))));
+    assert!(matches!(rest[1], Ok(recognizer::Event::SessionEnded(_))));
+}
+
+#[tokio::test]
+async fn functional_stop_paced_audio() {
+    let address = "127.0.0.1:4582";
+    let (tx, mut received) = tokio::sync::mpsc::unbounded_channel();
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![
+            move |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                let tx = tx.clone();
+                Box::pin(async move {
+                    let request_id = match ws.next().await {
+                        Some(Ok(msg)) => Message::try_from(msg).unwrap().id,
+                        _ => return,
+                    };
+
+                    // speech.context and audio header
+                    ws.next().await;
+                    ws.next().await;
+
+                    send_messages(&mut ws, &request_id, &[("turn.start", None)]).await;
+
+                    // Count the audio until the end of audio sent by stop().
+                    let mut audio = 0;
+                    while let Some(Ok(msg)) = ws.next().await {
+                        match Message::try_from(msg).unwrap().data {
+                            Data::Binary(Some(data)) => audio += data.len(),
+                            _ => break,
+                        }
+                    }
+                    let _ = tx.send(audio);
+
+                    send_messages(&mut ws, &request_id, &[("turn.end", None)]).await;
+                    while ws.next().await.is_some() {}
+                })
+            },
+        ]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(
+        client,
+        recognizer::Config::default().set_audio_pacing(
+            recognizer::AudioPacing::new(1.0).with_burst(std::time::Duration::ZERO),
+        ),
+    );
+
+    // 10 seconds of audio, read at once: the pacing sends it in 10 seconds.
+    let mut events = recognizer
+        .recognize(
+            tokio_stream::iter(vec![vec![0; 320_000]]),
+            recognizer::AudioFormat::RawPcm {
+                sample_rate: 16000,
+                bits_per_sample: 16,
+                channels: 1,
+            },
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        events.next().await,
+        Some(Ok(recognizer::Event::SessionStarted(_)))
+    ));
+
+    // The stop is handled while the pacing waits.
+    events.stop().await;
+
+    let rest = tokio::time::timeout(
+        std::time::Duration::from_secs(1),
+        events.collect::<Vec<_>>(),
+    )
+    .await
+    .expect("stream to end after stop");
+
+    assert!(matches!(
+        rest.as_slice(),
+        [Ok(recognizer::Event::SessionEnded(_))]
+    ));
+    assert!(received.recv().await.unwrap() < 320_000 / 2);
+}
+
+#[tokio::test]
+async fn functional_recognize_abort() {
+    let address = "127.0.0.1:4572";
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![
+            |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                Box::pin(async move {
+                    // Never answer: the client must not wait for any result.
+                    while ws.next().await.is_some() {}
+                })
+            },
+        ]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(client, recognizer::Config::default());
+
+    for _ in 0..2 {
+        let events = recognizer
+            .recognize(
+                tokio_stream::pending(),
+                recognizer::AudioFormat::Mp3,
+                recognizer::AudioDevice::unknown(),
+            )
+            .await
+            .unwrap();
+
+        let handle = events.handle();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            handle.abort();
+        });
+
+        let events = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            events.collect::<Vec<_>>(),
+        )
+        .await
+        .expect("stream to end after abort");
+
+        assert!(events.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn functional_recognize_raw_pcm() {
+    let address = "127.0.0.1:4573";
+    let (tx, mut received) = tokio::sync::mpsc::unbounded_channel();
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![
+            move |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                let tx = tx.clone();
+                Box::pin(async move {
+                    // speech.config, speech.context and audio header
+                    for _ in 0..3 {
+                        if let Some(Ok(msg)) = ws.next().await {
+                            let _ = tx.send(Message::try_from(msg).unwrap());
+                        }
+                    }
+                    while ws.next().await.is_some() {}
+                })
+            },
+        ]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(client, recognizer::Config::default());
+
+    let _events = recognizer
+        .recognize(
+            tokio_stream::pending(),
+            recognizer::AudioFormat::RawPcm {
+                sample_rate: 8000,
+                bits_per_sample: 16,
+                channels: 1,
+            },
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap();
+
+    let mut messages = vec![];
+    for _ in 0..3 {
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(1), received.recv())
+            .await
+            .expect("message")
+            .unwrap();
+        messages.push(msg);
+    }
+
+    match &messages[0].data {
+        Data::Text(Some(body)) => {
+            let v: serde_json::Value = serde_json::from_str(body).unwrap();
+            let source = &v["context"]["audio"]["source"];
+            assert_eq!(source["samplerate"], 8000);
+            assert_eq!(source["bitspersample"], 16);
+            assert_eq!(source["channelcount"], 1);
+        }
+        _ => panic!("expected text body"),
+    }
+
+    assert_eq!(messages[1].path, "speech.context");
+    assert_eq!(messages[2].path, "audio");
+    match &messages[2].data {
+        Data::Binary(Some(data)) => {
+            assert_eq!(data.len(), 44);
+            assert_eq!(&data[0..4], b"RIFF");
+            assert_eq!(u32::from_le_bytes(data[24..28].try_into().unwrap()), 8000);
+        }
+        _ => panic!("expected binary body"),
+    }
+}
+
+#[tokio::test]
+async fn functional_long_silence_keeps_the_connection() {
+    let address = "127.0.0.1:4586";
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![
+            |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                Box::pin(async move {
+                    let request_id = match ws.next().await {
+                        Some(Ok(msg)) => Message::try_from(msg).unwrap().id,
+                        _ => return,
+                    };
+                    // speech.context and audio header
+                    for _ in 0..2 {
+                        ws.next().await;
+                    }
+                    send_messages(&mut ws, &request_id, &[("turn.start", None)]).await;
+
+                    // Like the service, only answer to the audio received.
+                    while let Some(Ok(msg)) = ws.next().await {
+                        match Message::try_from(msg).unwrap().data {
+                            Data::Binary(Some(_)) => {
+                                send_messages(
+                                    &mut ws,
+                                    &request_id,
+                                    &[(
+                                        "speech.phrase",
+                                        Some(r#"{"RecognitionStatus":"InitialSilenceTimeout","Offset":0,"Duration":0}"#),
+                                    )],
+                                )
+                                .await
+                            }
+                            _ => break,
+                        }
+                    }
+                    send_messages(&mut ws, &request_id, &[("turn.end", None)]).await;
+                    while ws.next().await.is_some() {}
+                })
+            },
+        ]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(
+        client,
+        recognizer::Config::default()
+            .set_voice_activity_detection(recognizer::VoiceActivityDetection::new()),
+    );
+
+    // 32 s of silence, in real time: longer than the 30 s without messages from the service.
+    let (audio_tx, audio_rx) = tokio::sync::mpsc::channel(1);
+    tokio::spawn(async move {
+        for _ in 0..32 {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            if audio_tx.send(vec![0u8; 32000]).await.is_err() {
+                break;
+            }
+        }
+    });
+    let events = recognizer
+        .recognize(
+            tokio_stream::wrappers::ReceiverStream::new(audio_rx),
+            recognizer::AudioFormat::RawPcm {
+                sample_rate: 16000,
+                bits_per_sample: 16,
+                channels: 1,
+            },
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .collect::<Vec<_>>();
+    let events = tokio::time::timeout(std::time::Duration::from_secs(45), events)
+        .await
+        .expect("stream to end");
+
+    assert!(events.iter().all(Result::is_ok), "{events:?}");
+    assert!(
+        matches!(events.last(), Some(Ok(recognizer::Event::SessionEnded(_)))),
+        "{events:?}"
+    );
+}
+
+#[tokio::test]
+async fn functional_identify_languages() {
+    let address = "127.0.0.1:4574";
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![
+            move |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                Box::pin(async move {
+                    let request_id = match ws.next().await {
+                        Some(Ok(msg)) => Message::try_from(msg).unwrap().id,
+                        _ => return,
+                    };
+
+                    // speech.context and audio header
+                    ws.next().await;
+                    ws.next().await;
+
+                    use crate::common::make_text_payload;
+
+                    for (path, data) in [
+                        ("turn.start", None),
+                        ("speech.hypothesis", Some(r#"{"Text":"ciao","Offset":100,"Duration":200}"#)),
+                        ("speech.phrase", Some(r#"{"RecognitionStatus":"Success","Offset":100,"Duration":500,"PrimaryLanguage":{"Language":"it-IT","Confidence":"High"}}"#)),
+                        ("turn.end", None),
+                    ] {
+                        let payload = make_text_payload(
+                            vec![
+                                ("X-RequestId".to_string(), request_id.clone()),
+                                ("Path".to_string(), path.to_string()),
+                            ],
+                            data,
+                        );
+                        ws.send(tokio_websockets::Message::text(payload))
+                            .await
+                            .unwrap();
+                    }
+
+                    while ws.next().await.is_some() {}
+                })
+            },
+        ]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(
+        client,
+        recognizer::Config::default().set_identify_languages(
+            vec![recognizer::Language::EnUs, recognizer::Language::ItIt],
+            recognizer::LanguageDetectMode::AtStart,
+        ),
+    );
+
+    let events = recognizer
+        .recognize(
+            tokio_stream::iter(vec![]),
+            recognizer::AudioFormat::Mp3,
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .filter_map(|event| async move { event.ok() })
+        .collect::<Vec<_>>();
+    let events = tokio::time::timeout(std::time::Duration::from_secs(5), events)
+        .await
+        .expect("stream to end");
+
+    assert_eq!(events.len(), 3, "{events:?}");
+    match &events[1] {
+        recognizer::Event::LanguageDetected(detected) => {
+            assert_eq!(detected.language().language, recognizer::Language::ItIt);
+            assert_eq!(detected.language().confidence, recognizer::Confidence::High);
+            assert_eq!(
+                (detected.offset(), detected.duration()),
+                (Ticks::new(100), Ticks::new(500))
+            );
+        }
+        e => panic!("Expected LanguageDetected, got {:?}", e),
+    }
+}
+
+#[tokio::test]
+async fn functional_unmatch_reasons() {
+    let address = "127.0.0.1:4579";
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![
+            move |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                Box::pin(async move {
+                    let request_id = match ws.next().await {
+                        Some(Ok(msg)) => Message::try_from(msg).unwrap().id,
+                        _ => return,
+                    };
+
+                    // speech.context and audio header
+                    ws.next().await;
+                    ws.next().await;
+
+                    send_messages(
+                        &mut ws,
+                        &request_id,
+                        &[
+                            ("turn.start", None),
+                            ("speech.phrase", Some(r#"{"RecognitionStatus":"InitialSilenceTimeout","Offset":0,"Duration":50000000}"#)),
+                            ("speech.phrase", Some(r#"{"RecognitionStatus":"BabbleTimeout","Offset":50000000,"Duration":10000000}"#)),
+                            ("speech.phrase", Some(r#"{"RecognitionStatus":"NoMatch","Offset":60000000,"Duration":500}"#)),
+                            ("speech.phrase", Some(r#"{"RecognitionStatus":"KeywordNotRecognized","Offset":60000500,"Duration":500}"#)),
+                            ("turn.end", None),
+                        ],
+                    )
+                    .await;
+
+                    while ws.next().await.is_some() {}
+                })
+            },
+        ]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(client, recognizer::Config::default());
+
+    let reasons = recognizer
+        .recognize(
+            tokio_stream::iter(vec![]),
+            recognizer::AudioFormat::Mp3,
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .filter_map(|event| async move {
+            match event {
+                Ok(recognizer::Event::UnMatch(unmatch)) => Some(unmatch.reason()),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    let reasons = tokio::time::timeout(std::time::Duration::from_secs(5), reasons)
+        .await
+        .expect("stream to end");
+
+    assert_eq!(
+        reasons,
+        vec![
+            recognizer::NoMatchReason::InitialSilenceTimeout,
+            recognizer::NoMatchReason::InitialBabbleTimeout,
+            recognizer::NoMatchReason::NotRecognized,
+            recognizer::NoMatchReason::KeywordNotRecognized,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn functional_result_filter() {
+    let address = "127.0.0.1:4580";
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![
+            move |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                Box::pin(async move {
+                    let request_id = match ws.next().await {
+                        Some(Ok(msg)) => Message::try_from(msg).unwrap().id,
+                        _ => return,
+                    };
+
+                    // speech.context and audio header
+                    ws.next().await;
+                    ws.next().await;
+
+                    send_messages(
+                        &mut ws,
+                        &request_id,
+                        &[
+                            ("turn.start", None),
+                            ("speech.phrase", Some(r#"{"RecognitionStatus":"Success","Offset":0,"Duration":500,"DisplayText":"Hmm.","NBest":[{"Confidence":0.3,"Lexical":"hmm","ITN":"hmm","MaskedITN":"hmm","Display":"Hmm."}]}"#)),
+                            ("speech.phrase", Some(r#"{"RecognitionStatus":"Success","Offset":1000,"Duration":500,"DisplayText":"Call Jon.","NBest":[{"Confidence":0.7,"Lexical":"call jon","ITN":"call jon","MaskedITN":"call jon","Display":"Call Jon."},{"Confidence":0.6,"Lexical":"call joan","ITN":"call joan","MaskedITN":"call joan","Display":"Call Joan."}]}"#)),
+                            ("turn.end", None),
+                        ],
+                    )
+                    .await;
+
+                    while ws.next().await.is_some() {}
+                })
+            },
+        ]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(
+        client,
+        recognizer::Config::default()
+            .set_phrases(vec!["Joan".to_string()])
+            .set_result_filter(
+                recognizer::ResultFilter::new()
+                    .with_min_confidence(0.5)
+                    .with_selection(recognizer::AlternateSelection::PreferPhrases),
+            ),
+    );
+
+    let events = recognizer
+        .recognize(
+            tokio_stream::iter(vec![]),
+            recognizer::AudioFormat::Mp3,
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .filter_map(|event| async move {
+            match event {
+                Ok(recognizer::Event::UnMatch(unmatch)) => Some(format!("{:?}", unmatch.reason())),
+                Ok(recognizer::Event::Recognized(recognized)) => {
+                    Some(recognized.result().text.clone())
+                }
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    let events = tokio::time::timeout(std::time::Duration::from_secs(5), events)
+        .await
+        .expect("stream to end");
+
+    assert_eq!(events, vec!["LowConfidence", "Call Joan."]);
+}
+
+/// A server that recognizes one channel: the reply depends on the audio received.
+fn channel_server(
+) -> impl Fn(WebSocketStream<TcpStream>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Clone {
+    move |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(async move {
+            let request_id = match ws.next().await {
+                Some(Ok(msg)) => Message::try_from(msg).unwrap().id,
+                _ => return,
+            };
+
+            // speech.context
+            ws.next().await;
+
+            let mut header = None;
+            let mut audio = vec![];
+            while let Some(Ok(msg)) = ws.next().await {
+                match Message::try_from(msg).unwrap().data {
+                    Data::Binary(Some(data)) if header.is_none() => header = Some(data),
+                    Data::Binary(Some(data)) => audio.extend(data),
+                    _ => break,
+                }
+            }
+            // The audio of each channel is sent as mono.
+            assert_eq!(u16::from_le_bytes([header.unwrap()[22], 0]), 1);
+
+            let phrase = if audio.iter().all(|&b| b == 1) {
+                r#"{"RecognitionStatus":"Success","DisplayText":"Agent.","Offset":2000,"Duration":500}"#
+            } else if audio.iter().all(|&b| b == 2) {
+                r#"{"RecognitionStatus":"Success","DisplayText":"Customer.","Offset":1000,"Duration":500}"#
+            } else {
+                panic!("Unexpected audio: {:?}", audio);
+            };
+            send_messages(
+                &mut ws,
+                &request_id,
+                &[
+                    ("turn.start", None),
+                    ("speech.phrase", Some(phrase)),
+                    ("turn.end", None),
+                ],
+            )
+            .await;
+
+            while ws.next().await.is_some() {}
+        })
+    }
+}
+
+#[tokio::test]
+async fn functional_split_channels() {
+    let address = "127.0.0.1:4581";
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![channel_server(), channel_server()]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(
+        client,
+        recognizer::Config::default().set_channel_mode(recognizer::ChannelMode::Split),
+    );
+
+    // Stereo 16 bits audio: the left samples are 0x0101, the right ones 0x0202.
+    let audio = [1u8, 1, 2, 2].repeat(1000);
+    let events = recognizer
+        .recognize(
+            tokio_stream::iter(audio.chunks(1500).map(|c| c.to_vec()).collect::<Vec<_>>()),
+            recognizer::AudioFormat::RawPcm {
+                sample_rate: 16000,
+                bits_per_sample: 16,
+                channels: 2,
+            },
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .filter_map(|event| async move {
+            match event {
+                Ok(recognizer::Event::Recognized(recognized)) => Some((
+                    recognized.channel(),
+                    recognized.result().text.clone(),
+                    recognized.offset().as_ticks(),
+                )),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    let events = tokio::time::timeout(std::time::Duration::from_secs(5), events)
+        .await
+        .expect("stream to end");
+
+    assert_eq!(
+        events,
+        vec![
+            (Some(1), "Customer.".to_string(), 1000),
+            (Some(0), "Agent.".to_string(), 2000),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn functional_split_channels_with_connection_lost() {
+    let address = "127.0.0.1:4585";
+
+    // The server only accepts the connection of each channel: the reconnections are refused.
+    let listener = tokio::net::TcpListener::bind(address).await.unwrap();
+    tokio::spawn(async move {
+        // The connection of the first channel is lost after the handshake.
+        let (stream, _) = listener.accept().await.unwrap();
+        let (_, mut ws) = tokio_websockets::ServerBuilder::new()
+            .accept(stream)
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            // speech.config, speech.context and audio header
+            for _ in 0..3 {
+                ws.next().await;
+            }
+            let _ = ws.close().await;
+        });
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let (_, ws) = tokio_websockets::ServerBuilder::new()
+            .accept(stream)
+            .await
+            .unwrap();
+        tokio::spawn(channel_server()(ws));
+    });
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(
+        client,
+        recognizer::Config::default().set_channel_mode(recognizer::ChannelMode::Split),
+    );
+
+    // Many more chunks than the audio buffered for each channel, most after the connection is lost.
+    let (audio_tx, audio_rx) = tokio::sync::mpsc::channel(1);
+    tokio::spawn(async move {
+        for _ in 0..100 {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            if audio_tx.send([1u8, 1, 2, 2].repeat(1000)).await.is_err() {
+                break;
+            }
+        }
+    });
+    let events = recognizer
+        .recognize(
+            tokio_stream::wrappers::ReceiverStream::new(audio_rx),
+            recognizer::AudioFormat::RawPcm {
+                sample_rate: 16000,
+                bits_per_sample: 16,
+                channels: 2,
+            },
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .filter_map(|event| async move {
+            match event {
+                Ok(recognizer::Event::Recognized(recognized)) => {
+                    Some(Ok((recognized.channel(), recognized.result().text.clone())))
+                }
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            }
+        })
+        .collect::<Vec<_>>();
+    let events = tokio::time::timeout(std::time::Duration::from_secs(5), events)
+        .await
+        .expect("stream to end");
+
+    // The audio of the second channel is still recognized.
+    assert!(
+        matches!(
+            events.as_slice(),
+            [Err(azure_speech::Error::ConnectionError(_)), Ok((Some(1), text))] if text == "Customer."
+        ),
+        "{events:?}"
+    );
+}
+
+const TWO_TURNS_PHRASES: [&str; 2] = [
+    r#"{"RecognitionStatus":"Success","DisplayText":"Hello.","Offset":100,"Duration":500}"#,
+    r#"{"RecognitionStatus":"Success","DisplayText":"World.","Offset":50,"Duration":200}"#,
+];
+
+/// A server that sends a phrase in two turns, the second one after the new audio header.
+fn two_turns_server(
+    audio_chunks: usize,
+    phrases: [&'static str; 2],
+) -> impl Fn(WebSocketStream<TcpStream>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Clone {
+    move |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(async move {
+            use crate::common::make_text_payload;
+
+            let mut request_id = match ws.next().await {
+                Some(Ok(msg)) => Message::try_from(msg).unwrap().id,
+                _ => return,
+            };
+
+            // speech.context, audio header and the audio chunks
+            for _ in 0..2 + audio_chunks {
+                ws.next().await;
+            }
+
+            for phrase in phrases {
+                for (path, data) in [
+                    ("turn.start", None),
+                    ("speech.phrase", Some(phrase)),
+                    ("turn.end", None),
+                ] {
+                    let payload = make_text_payload(
+                        vec![
+                            ("X-RequestId".to_string(), request_id.clone()),
+                            ("Path".to_string(), path.to_string()),
+                        ],
+                        data,
+                    );
+                    ws.send(tokio_websockets::Message::text(payload))
+                        .await
+                        .unwrap();
+                }
+
+                // Wait for the audio header of the next turn.
+                while let Some(Ok(msg)) = ws.next().await {
+                    let msg = Message::try_from(msg).unwrap();
+                    if msg.id != request_id {
+                        request_id = msg.id;
+                        break;
+                    }
+                }
+            }
+
+            while ws.next().await.is_some() {}
+        })
+    }
+}
+
+async fn recognized_offsets(
+    address: &str,
+    audio: Vec<Vec<u8>>,
+    audio_format: recognizer::AudioFormat,
+) -> Vec<(String, Ticks, Ticks)> {
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(client, recognizer::Config::default());
+
+    let events = recognizer
+        .recognize(
+            tokio_stream::StreamExt::chain(tokio_stream::iter(audio), tokio_stream::pending()),
+            audio_format,
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .filter_map(|event| async move {
+            match event {
+                Ok(recognizer::Event::Recognized(recognized)) => Some((
+                    recognized.result().text.clone(),
+                    recognized.offset(),
+                    recognized.duration(),
+                )),
+                _ => None,
+            }
+        })
+        .take(2)
+        .collect::<Vec<_>>();
+
+    tokio::time::timeout(std::time::Duration::from_secs(5), events)
+        .await
+        .expect("two phrases")
+}
+
+#[tokio::test]
+async fn functional_offsets_across_turns_after_last_phrase() {
+    let address = "127.0.0.1:4575";
+    common::start_server(address, VecDeque::from_iter(vec![two_turns_server(0, TWO_TURNS_PHRASES)])).await;
+
+    // The duration of compressed audio is unknown: the next turn starts after the last phrase.
+    let phrases = recognized_offsets(address, vec![], recognizer::AudioFormat::Mp3).await;
+
+    assert_eq!(
+        phrases,
+        vec![
+            ("Hello.".to_string(), Ticks::new(100), Ticks::new(500)),
+            ("World.".to_string(), Ticks::new(650), Ticks::new(200)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn functional_offsets_across_turns_after_sent_audio() {
+    let address = "127.0.0.1:4576";
+    common::start_server(address, VecDeque::from_iter(vec![two_turns_server(3, TWO_TURNS_PHRASES)])).await;
+
+    // 3 chunks of 4096 bytes of 16kHz 16 bits mono audio are 384ms long.
+    let phrases = recognized_offsets(
+        address,
+        vec![vec![0; 4096 * 3]],
+        recognizer::AudioFormat::RawPcm {
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            channels: 1,
+        },
+    )
+    .await;
+
+    assert_eq!(
+        phrases,
+        vec![
+            ("Hello.".to_string(), Ticks::new(100), Ticks::new(500)),
+            ("World.".to_string(), Ticks::new(3_840_050), Ticks::new(200)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn functional_word_offsets_across_turns() {
+    let address = "127.0.0.1:4584";
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![two_turns_server(
+            0,
+            [
+                TWO_TURNS_PHRASES[0],
+                r#"{"RecognitionStatus":"Success","DisplayText":"World.","Offset":50,"Duration":200,
+                    "NBest":[{"Confidence":0.9,"Lexical":"world","ITN":"world","MaskedITN":"world","Display":"World.",
+                    "PronunciationAssessment":{"AccuracyScore":90.0},
+                    "Words":[{"Word":"world","Offset":50,"Duration":200,
+                        "PronunciationAssessment":{"AccuracyScore":90.0,"ErrorType":"None"},
+                        "Phonemes":[{"Phoneme":"w","Offset":80,"Duration":60}]}]}]}"#,
+            ],
+        )]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(
+        client,
+        recognizer::Config::default().set_pronunciation_assessment(
+            recognizer::PronunciationAssessmentConfig::new("Hello world")
+                .with_granularity(recognizer::Granularity::Phoneme),
+        ),
+    );
+
+    let events = recognizer
+        .recognize(
+            tokio_stream::pending(),
+            recognizer::AudioFormat::Mp3,
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .filter_map(|event| async move {
+            match event {
+                Ok(recognizer::Event::Recognized(recognized)) => Some(recognized),
+                _ => None,
+            }
+        })
+        .take(2)
+        .collect::<Vec<_>>();
+
+    let phrases = tokio::time::timeout(std::time::Duration::from_secs(5), events)
+        .await
+        .expect("two phrases");
+
+    // The second turn starts after the first phrase, as the words of its phrase do.
+    let world = &phrases[1];
+    assert_eq!(world.offset(), Ticks::new(650));
+    let assessment = world
+        .result()
+        .pronunciation_assessment
+        .as_ref()
+        .expect("pronunciation assessment");
+    assert_eq!(assessment.words[0].offset, Ticks::new(650));
+    assert_eq!(assessment.words[0].duration, Ticks::new(200));
+    assert_eq!(assessment.words[0].phonemes[0].offset, Ticks::new(680));
+}
+
+/// Send the messages of a turn to the client.
+async fn send_messages(
+    ws: &mut WebSocketStream<TcpStream>,
+    request_id: &str,
+    messages: &[(&str, Option<&str>)],
+) {
+    for &(path, data) in messages {
+        let payload = common::make_text_payload(
+            vec![
+                ("X-RequestId".to_string(), request_id.to_string()),
+                ("Path".to_string(), path.to_string()),
+            ],
+            data,
+        );
+        ws.send(tokio_websockets::Message::text(payload))
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn functional_resume_after_connection_lost() {
+    let address = "127.0.0.1:4577";
+    let (tx, mut received) = tokio::sync::mpsc::unbounded_channel();
+
+    let connections = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let server =
+        move |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+            let tx = tx.clone();
+            let first = connections.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0;
+            Box::pin(async move {
+                // speech.config, speech.context, audio header and the audio: 4 chunks, then the 3 replayed.
+                let mut messages = vec![];
+                for _ in 0..if first { 7 } else { 6 } {
+                    if let Some(Ok(msg)) = ws.next().await {
+                        messages.push(Message::try_from(msg).unwrap());
+                    }
+                }
+                let request_id = messages[0].id.clone();
+
+                let phrase = if first {
+                    r#"{"RecognitionStatus":"Success","DisplayText":"Hello.","Offset":0,"Duration":1280000}"#
+                } else {
+                    r#"{"RecognitionStatus":"Success","DisplayText":"World.","Offset":100,"Duration":500}"#
+                };
+                send_messages(
+                    &mut ws,
+                    &request_id,
+                    &[("turn.start", None), ("speech.phrase", Some(phrase))],
+                )
+                .await;
+
+                let _ = tx.send(
+                    messages
+                        .into_iter()
+                        .map(|message| match message.data {
+                            Data::Binary(Some(data)) => {
+                                format!("{} {} {}", message.id, message.path, data.len())
+                            }
+                            _ => format!("{} {}", message.id, message.path),
+                        })
+                        .collect::<Vec<_>>(),
+                );
+
+                if first {
+                    // The connection drops during the turn, after the first chunk is recognized.
+                    let _ = ws.close().await;
+                } else {
+                    send_messages(&mut ws, &request_id, &[("turn.end", None)]).await;
+                    while ws.next().await.is_some() {}
+                }
+            })
+        };
+    common::start_server(address, VecDeque::from_iter(vec![server.clone(), server])).await;
+
+    let phrases = recognized_offsets(
+        address,
+        vec![vec![0; 4096 * 4]],
+        recognizer::AudioFormat::RawPcm {
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            channels: 1,
+        },
+    )
+    .await;
+
+    // The second phrase is in the audio sent again, after the first phrase.
+    assert_eq!(
+        phrases,
+        vec![
+            ("Hello.".to_string(), Ticks::new(0), Ticks::new(1_280_000)),
+            ("World.".to_string(), Ticks::new(1_280_100), Ticks::new(500)),
+        ]
+    );
+
+    let first = received.recv().await.unwrap();
+    let second = received.recv().await.unwrap();
+    let request_id = &second[0][..second[0].find(' ').unwrap()];
+    assert!(!first[0].starts_with(request_id));
+    assert_eq!(
+        second,
+        [
+            "speech.config",
+            "speech.context",
+            "audio 44",
+            "audio 4096",
+            "audio 4096",
+            "audio 4096"
+        ]
+        .map(|message| format!("{request_id} {message}"))
+    );
+}
+
+#[tokio::test]
+async fn functional_resume_while_sending_audio() {
+    let address = "127.0.0.1:4583";
+    let (tx, mut received) = tokio::sync::mpsc::unbounded_channel();
+
+    let connections = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let server =
+        move |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+            let tx = tx.clone();
+            let first = connections.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0;
+            Box::pin(async move {
+                let request_id = match ws.next().await {
+                    Some(Ok(msg)) => Message::try_from(msg).unwrap().id,
+                    _ => return,
+                };
+                // speech.context and audio header
+                ws.next().await;
+                ws.next().await;
+
+                send_messages(&mut ws, &request_id, &[("turn.start", None)]).await;
+
+                if first {
+                    // The connection drops while the audio is still being sent.
+                    ws.next().await;
+                    ws.next().await;
+                    let _ = ws.close().await;
+                    return;
+                }
+
+                let mut audio = vec![];
+                while let Some(Ok(msg)) = ws.next().await {
+                    match Message::try_from(msg).unwrap().data {
+                        Data::Binary(Some(data)) => audio.extend(data),
+                        _ => break,
+                    }
+                }
+                let _ = tx.send(audio);
+
+                send_messages(
+                    &mut ws,
+                    &request_id,
+                    &[
+                        (
+                            "speech.phrase",
+                            Some(r#"{"RecognitionStatus":"Success","DisplayText":"Hello.","Offset":0,"Duration":100}"#),
+                        ),
+                        ("turn.end", None),
+                    ],
+                )
+                .await;
+                while ws.next().await.is_some() {}
+            })
+        };
+    common::start_server(address, VecDeque::from_iter(vec![server.clone(), server])).await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(client, recognizer::Config::default());
+
+    // 8 chunks, each with its own value, read while the connection drops.
+    let (audio_tx, audio_rx) = tokio::sync::mpsc::channel(1);
+    tokio::spawn(async move {
+        for value in 1..=8u8 {
+            if audio_tx.send(vec![value; 4096]).await.is_err() {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    });
+
+    let events = recognizer
+        .recognize(
+            tokio_stream::wrappers::ReceiverStream::new(audio_rx),
+            recognizer::AudioFormat::RawPcm {
+                sample_rate: 16000,
+                bits_per_sample: 16,
+                channels: 1,
+            },
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .collect::<Vec<_>>();
+    let events = tokio::time::timeout(std::time::Duration::from_secs(5), events)
+        .await
+        .expect("stream to end");
+
+    assert!(events.iter().all(Result::is_ok), "{events:?}");
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, Ok(recognizer::Event::Recognized(_)))));
+
+    // No phrase was recognized on the first connection: all the audio is sent on the second one.
+    let audio = received.recv().await.unwrap();
+    assert_eq!(
+        audio,
+        (1..=8u8)
+            .flat_map(|value| vec![value; 4096])
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn functional_connection_lost_without_replay() {
+    let address = "127.0.0.1:4578";
+
+    common::start_server(
+        address,
+        VecDeque::from_iter(vec![
+            move |mut ws: WebSocketStream<TcpStream>| -> Pin<Box<dyn Future<Output = ()> + Send>> {
+                Box::pin(async move {
+                    // speech.config, speech.context and audio header
+                    for _ in 0..3 {
+                        ws.next().await;
+                    }
+                    let _ = ws.close().await;
+                })
+            },
+        ]),
+    )
+    .await;
+
+    let uri = Uri::from_str(format!("ws://{}", address).as_str()).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let recognizer = recognizer::Client::new(
+        client,
+        recognizer::Config::default().set_replay_buffer_size(0),
+    );
+
+    let events = recognizer
+        .recognize(
+            tokio_stream::pending(),
+            recognizer::AudioFormat::Mp3,
+            recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .collect::<Vec<_>>();
+    let events = tokio::time::timeout(std::time::Duration::from_secs(5), events)
+        .await
+        .expect("stream to end");
+
+    assert!(
+        matches!(
+            events.as_slice(),
+            [Err(azure_speech::Error::ServerDisconnect(_))]
+        ),
+        "{events:?}"
+    );
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use azure_speech::{translator, Message, Ticks};
+use futures_util::{SinkExt, StreamExt};
+use http::Uri;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use tokio::net::TcpStream;
+use tokio_websockets::{ClientBuilder, WebSocketStream};
+
+fn translator_server(
+) -> impl Fn(WebSocketStream<TcpStream>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Clone {
+    |mut ws: WebSocketStream<TcpStream>| {
+        Box::pin(async move {
+            let request_id = match ws.next().await {
+                Some(Ok(msg)) => Message::try_from(msg).unwrap().id,
+                _ => return,
+            };
+
+            // speech.context, audio header and end of audio.
+            ws.next().await;
+            ws.next().await;
+            ws.next().await;
+
+            use crate::common::{make_binary_payload, make_text_payload};
+
+            let text = |path: &str, data: Option<&str>| {
+                tokio_websockets::Message::text(make_text_payload(
+                    vec![
+                        ("X-RequestId".to_string(), request_id.clone()),
+                        ("Path".to_string(), path.to_string()),
+                    ],
+                    data,
+                ))
+            };
+
+            let messages = vec![
+                text("turn.start", None),
+                text(
+                    "translation.hypothesis",
+                    Some(
+                        r#"{"Text":"turn on","Offset":100,"Duration":200,"Translation":{"TranslationStatus":"Success","Translations":[{"Language":"de","Text":"einschalten"}]}}"#,
+                    ),
+                ),
+                text(
+                    "translation.phrase",
+                    Some(
+                        r#"{"RecognitionStatus":"Success","Text":"Turn on the lamp.","Offset":100,"Duration":900,"Translation":{"TranslationStatus":"Success","Translations":[{"Language":"de","Text":"Schalte die Lampe ein."},{"Language":"it","Text":"Accendi la lampada."}]}}"#,
+                    ),
+                ),
+                tokio_websockets::Message::binary(make_binary_payload(
+                    vec![
+                        ("X-RequestId".to_string(), request_id.clone()),
+                        ("Path".to_string(), "translation.synthesis".to_string()),
+                    ],
+                    Some(&[1, 2, 3]),
+                )),
+                text(
+                    "translation.synthesis.end",
+                    Some(r#"{"SynthesisStatus":"Success"}"#),
+                ),
+                text("turn.end", None),
+            ];
+
+            for message in messages {
+                ws.send(message).await.unwrap();
+            }
+
+            // The connection stays open: the stream ends after the last turn.
+            while ws.next().await.is_some() {}
+        })
+    }
+}
+
+#[tokio::test]
+async fn functional_translator_events() {
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .try_init();
+
+    let address = "127.0.0.1:4568";
+
+    common::start_server(address, VecDeque::from_iter(vec![translator_server()])).await;
+
+    let uri = Uri::from_str(&format!("ws://{}", address)).unwrap();
+    let client = azure_speech::connector::Client::connect(ClientBuilder::from_uri(uri))
+        .await
+        .unwrap();
+
+    let translator = translator::Client::new(
+        client,
+        translator::Config::default()
+            .add_target_language("de")
+            .add_target_language("it"),
+    );
+
+    let events = translator
+        .translate(
+            tokio_stream::iter(vec![]),
+            azure_speech::recognizer::AudioFormat::Mp3,
+            azure_speech::recognizer::AudioDevice::unknown(),
+        )
+        .await
+        .unwrap()
+        .collect::<Vec<_>>();
+    let events = tokio::time::timeout(std::time::Duration::from_secs(5), events)
+        .await
+        .expect("stream to end");
+
+    assert!(matches!(
+        events[0],
+        Ok(translator::Event::SessionStarted(_))
+    ));
+    match &events[1] {
+        Ok(translator::Event::Translating(event)) => {
+            assert_eq!(event.result().text, "turn on");
+            assert_eq!(event.result().translations["de"], "einschalten");
+            assert_eq!(
+                (event.offset(), event.duration()),
+                (Ticks::new(100), Ticks::new(200))
+            );
+        }
+        e => panic!("Expected Translating, got {:?}", e),
+    }
+    match &events[2] {
+        Ok(translator::Event::Translated(event)) => {
+            let translated = event.result();
+            assert_eq!(translated.text, "Turn on the lamp.");
+            assert_eq!(translated.translations["de"], "Schalte die Lampe ein.");
+            assert_eq!(translated.translations["it"], "Accendi la lampada.");
+        }
+        e => panic!("Expected Translated, got {:?}", e),
+    }
+    match &events[3] {
+        Ok(translator::Event::Synthesising(_, audio)) => assert_eq!(audio, &vec![1, 2, 3]),
+        e => panic!("Expected Synthesising, got {:?}", e),
+    }
+    assert!(matches!(events[4], Ok(translator::Event::Synthesised(_))));
+    assert!(matches!(events[5], Ok(translator::Event::SessionEnded(_))));
+    assert_eq!(events.len(), 6);
+}